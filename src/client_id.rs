@@ -0,0 +1,134 @@
+/// ARP hardware type and address length of ethernet
+pub const ARPHRD_ETHER: (u8, usize) = (1, 6);
+/// ARP hardware type and address length of infiniband
+pub const ARPHRD_INFINIBAND: (u8, usize) = (32, 20);
+
+/// Values of `ipv4.dhcp-client-id` NetworkManager doesn't send as string.
+const NM_KEYWORDS: [&str; 6] = ["mac", "perm-mac", "ipv6-duid", "duid", "stable", "none"];
+
+/// A DHCPv4 client-id as wicked sends it.
+///
+/// Wicked first tries to parse the configured value as hardware address of the interface, then
+/// as colon separated hex bytes and finally sends it as string.
+#[derive(Debug, PartialEq)]
+pub enum ClientId {
+    /// A hardware address, sent after its ARP hardware type
+    HwAddr { hw_type: u8, address: Vec<u8> },
+    /// Bytes sent verbatim, the first one is the type (e.g. `ff` for RFC 4361 IAID and DUID)
+    Raw(Vec<u8>),
+    /// Any other value, sent after a zero type byte
+    Text(String),
+}
+
+impl ClientId {
+    /// Parses the wicked `client-id` of an interface with the given ARP hardware type and
+    /// address length.
+    pub fn parse(value: &str, (hw_type, hw_len): (u8, usize)) -> Option<Self> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        match parse_hex(value) {
+            Some(address) if address.len() == hw_len => Some(ClientId::HwAddr { hw_type, address }),
+            Some(bytes) => Some(ClientId::Raw(bytes)),
+            None => Some(ClientId::Text(value.to_string())),
+        }
+    }
+
+    /// The client-id bytes sent on the wire.
+    pub fn bytes(&self) -> Vec<u8> {
+        match self {
+            ClientId::HwAddr { hw_type, address } => {
+                let mut bytes = vec![*hw_type];
+                bytes.extend(address);
+                bytes
+            }
+            ClientId::Raw(bytes) => bytes.clone(),
+            ClientId::Text(text) => {
+                let mut bytes = vec![0];
+                bytes.extend(text.as_bytes());
+                bytes
+            }
+        }
+    }
+
+    /// The value of `ipv4.dhcp-client-id` that makes NetworkManager send the same bytes.
+    ///
+    /// NetworkManager needs a type and at least one more byte, shorter client-ids can't be
+    /// migrated.
+    pub fn to_nm(&self) -> Option<String> {
+        match self {
+            ClientId::Text(text) if !NM_KEYWORDS.contains(&text.as_str()) => Some(text.clone()),
+            _ => {
+                let bytes = self.bytes();
+                if bytes.len() < 2 {
+                    return None;
+                }
+                Some(
+                    bytes
+                        .iter()
+                        .map(|b| format!("{b:02x}"))
+                        .collect::<Vec<String>>()
+                        .join(":"),
+                )
+            }
+        }
+    }
+}
+
+/// Parses colon separated hex bytes like `01:aa:b`.
+fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    value
+        .split(':')
+        .map(|byte| {
+            if byte.is_empty() || byte.len() > 2 {
+                return None;
+            }
+            u8::from_str_radix(byte, 16).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_id() {
+        let tests = vec![
+            // A MAC address gets the ethernet type
+            ("52:54:00:dc:8e:94", Some("01:52:54:00:dc:8e:94")),
+            ("52:54:00:DC:8E:94", Some("01:52:54:00:dc:8e:94")),
+            // Other hex bytes are sent as they are
+            ("01:52:54:00:dc:8e:94", Some("01:52:54:00:dc:8e:94")),
+            (
+                "ff:00:00:00:01:00:03:00:01:52:54:00:12:34:56",
+                Some("ff:00:00:00:01:00:03:00:01:52:54:00:12:34:56"),
+            ),
+            ("0:a", Some("00:0a")),
+            // Strings keep their zero type byte
+            ("my-client", Some("my-client")),
+            ("mac", Some("00:6d:61:63")),
+            ("ab", None),
+            ("", None),
+        ];
+
+        for (value, expected) in tests {
+            let client_id = ClientId::parse(value, ARPHRD_ETHER);
+            assert_eq!(
+                client_id.as_ref().and_then(ClientId::to_nm).as_deref(),
+                expected,
+                "{value}"
+            );
+        }
+
+        assert_eq!(
+            ClientId::parse("my", ARPHRD_ETHER).unwrap().bytes(),
+            vec![0, b'm', b'y']
+        );
+        assert!(matches!(
+            ClientId::parse("52:54:00:dc:8e:94", ARPHRD_INFINIBAND),
+            Some(ClientId::Raw(_))
+        ));
+    }
+}
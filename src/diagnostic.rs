@@ -0,0 +1,196 @@
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use strum_macros::{Display, EnumString};
+
+/// Stable codes of all warnings and infos wicked2nm can emit.
+///
+/// The codes are part of the CLI interface (`--allow-warning`/`--deny-warning`),
+/// existing codes must not be renamed.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    SerializeDisplay,
+    DeserializeFromStr,
+    EnumString,
+    Display,
+)]
+pub enum Diagnostic {
+    /// A wicked field that isn't migrated
+    #[strum(serialize = "W2NM-UNHANDLED-FIELD")]
+    UnhandledField,
+    /// A deprecated ifsysctl file that isn't migrated
+    #[strum(serialize = "W2NM-IFSYSCTL-DEPRECATED")]
+    IfsysctlDeprecated,
+    /// A port whose controller connection doesn't exist
+    #[strum(serialize = "W2NM-MISSING-PARENT")]
+    MissingParent,
+    /// An excluded interface a selected interface depends on
+    #[strum(serialize = "W2NM-EXCLUDED-DEPENDENCY")]
+    ExcludedDependency,
+    /// A custom ipv4 broadcast address NetworkManager can't set
+    #[strum(serialize = "W2NM-CUSTOM-BROADCAST")]
+    CustomBroadcast,
+    /// A route that can't be parsed or converted
+    #[strum(serialize = "W2NM-INVALID-ROUTE")]
+    InvalidRoute,
+    /// A multipath route nexthop on another device
+    #[strum(serialize = "W2NM-MULTIPATH-DEVICE")]
+    MultipathDevice,
+    /// Multipath route weights NetworkManager can't apply
+    #[strum(serialize = "W2NM-MULTIPATH-WEIGHT")]
+    MultipathWeight,
+    /// A route protocol NetworkManager doesn't support
+    #[strum(serialize = "W2NM-ROUTE-PROTOCOL")]
+    RouteProtocol,
+    /// A routing rule that can't be parsed or converted
+    #[strum(serialize = "W2NM-INVALID-ROUTING-RULE")]
+    InvalidRoutingRule,
+    /// An ipv6 address generation mode NetworkManager handles differently
+    #[strum(serialize = "W2NM-IPV6-ADDR-GEN-MODE")]
+    Ipv6AddrGenMode,
+    /// Disabled ipv6 router advertisements changing the ipv6 method
+    #[strum(serialize = "W2NM-IPV6-ACCEPT-RA")]
+    Ipv6AcceptRa,
+    /// A DHCPv6 mode or prefix delegation setting NetworkManager handles differently
+    #[strum(serialize = "W2NM-IPV6-DHCP-MODE")]
+    Ipv6DhcpMode,
+    /// An --ipv6-shared interface that isn't in the configuration
+    #[strum(serialize = "W2NM-IPV6-SHARED-UNKNOWN")]
+    Ipv6SharedUnknown,
+    /// A DHCP update entry NetworkManager handles differently
+    #[strum(serialize = "W2NM-DHCP-UPDATE")]
+    DhcpUpdate,
+    /// A DHCP MTU wicked ignored but NetworkManager applies
+    #[strum(serialize = "W2NM-DHCP-MTU")]
+    DhcpMtu,
+    /// A DHCP client-id NetworkManager can't send with the same bytes
+    #[strum(serialize = "W2NM-DHCP-CLIENT-ID")]
+    DhcpClientId,
+    /// A DHCP FQDN setting NetworkManager handles differently
+    #[strum(serialize = "W2NM-DHCP-FQDN")]
+    DhcpFqdn,
+    /// A DHCP user class NetworkManager can't send as configured
+    #[strum(serialize = "W2NM-DHCP-USER-CLASS")]
+    DhcpUserClass,
+    /// A DHCP vendor class NetworkManager can't send
+    #[strum(serialize = "W2NM-DHCP-VENDOR-CLASS")]
+    DhcpVendorClass,
+    /// A disabled DHCP lease recovery NetworkManager always does
+    #[strum(serialize = "W2NM-DHCP-RECOVER-LEASE")]
+    DhcpRecoverLease,
+    /// A DHCPv6 lease of wicked that isn't imported
+    #[strum(serialize = "W2NM-DHCP6-LEASE")]
+    Dhcp6Lease,
+    /// A wicked state or lease file that can't be read
+    #[strum(serialize = "W2NM-WICKED-STATE")]
+    WickedState,
+    /// Ethernet link settings NetworkManager rejects
+    #[strum(serialize = "W2NM-ETHERNET-LINK")]
+    EthernetLink,
+    /// Wake-on-LAN settings NetworkManager can't apply
+    #[strum(serialize = "W2NM-WAKE-ON-LAN")]
+    WakeOnLan,
+    /// An infiniband multicast setting NetworkManager doesn't support
+    #[strum(serialize = "W2NM-INFINIBAND-MULTICAST")]
+    InfinibandMulticast,
+    /// An ifcfg variable with a value that can't be parsed
+    #[strum(serialize = "W2NM-IFCFG-INVALID-VALUE")]
+    IfcfgInvalidValue,
+    /// The unsupported STATIC_FALLBACK netconfig DNS policy
+    #[strum(serialize = "W2NM-NETCONFIG-STATIC-FALLBACK")]
+    NetconfigStaticFallback,
+    /// An invalid static netconfig DNS server
+    #[strum(serialize = "W2NM-NETCONFIG-INVALID-DNS-SERVER")]
+    NetconfigInvalidDnsServer,
+    /// A SEND_GRATUITOUS_ARP setting that isn't migrated
+    #[strum(serialize = "W2NM-NETCONFIG-GRATUITOUS-ARP")]
+    NetconfigGratuitousArp,
+    /// A missing netconfig dhcp file
+    #[strum(serialize = "W2NM-NETCONFIG-DHCP-MISSING")]
+    NetconfigDhcpMissing,
+    /// A wireless interface with multiple networks, split into connections
+    #[strum(serialize = "W2NM-WIRELESS-MULTIPLE-NETWORKS")]
+    WirelessMultipleNetworks,
+    /// An unsupported wireless key management, a supported one is guessed
+    #[strum(serialize = "W2NM-WIRELESS-KEY-MANAGEMENT")]
+    WirelessKeyManagement,
+    /// A wireless channel without band, the band is guessed
+    #[strum(serialize = "W2NM-WIRELESS-BAND")]
+    WirelessBand,
+    /// An unsupported wireless cipher
+    #[strum(serialize = "W2NM-WIRELESS-CIPHER")]
+    WirelessCipher,
+    /// A team interface converted to a bond
+    #[strum(serialize = "W2NM-TEAM-CONVERTED")]
+    TeamConverted,
+    /// A team port queue_id bonds don't support
+    #[strum(serialize = "W2NM-TEAM-QUEUE-ID")]
+    TeamQueueId,
+    /// A team port lacp_key bonds don't support
+    #[strum(serialize = "W2NM-TEAM-LACP-KEY")]
+    TeamLacpKey,
+    /// A team port lacp_prio the kernel may not support for bonds
+    #[strum(serialize = "W2NM-TEAM-LACP-PRIO")]
+    TeamLacpPrio,
+    /// A sticky team port without bond primary
+    #[strum(serialize = "W2NM-TEAM-STICKY")]
+    TeamSticky,
+    /// Team ports sharing the highest prio, no bond primary is set
+    #[strum(serialize = "W2NM-TEAM-PRIO-AMBIGUOUS")]
+    TeamPrioAmbiguous,
+    /// Team port priorities reduced to bond primary and backups
+    #[strum(serialize = "W2NM-TEAM-PRIO-LEVELS")]
+    TeamPrioLevels,
+    /// The team port with the highest prio becoming the bond primary
+    #[strum(serialize = "W2NM-TEAM-PRIO-PRIMARY")]
+    TeamPrioPrimary,
+    /// A team mcast_rejoin.interval bonds don't support
+    #[strum(serialize = "W2NM-TEAM-MCAST-REJOIN-INTERVAL")]
+    TeamMcastRejoinInterval,
+    /// A team link_watch_policy bonds don't support
+    #[strum(serialize = "W2NM-TEAM-LINK-WATCH-POLICY")]
+    TeamLinkWatchPolicy,
+    /// A team sys_prio adjusted to the bond range
+    #[strum(serialize = "W2NM-TEAM-SYS-PRIO")]
+    TeamSysPrio,
+    /// A team select_policy approximated by the bond ad_select
+    #[strum(serialize = "W2NM-TEAM-SELECT-POLICY")]
+    TeamSelectPolicy,
+    /// A team tx_balancer bonds can't rebalance
+    #[strum(serialize = "W2NM-TEAM-TX-BALANCER")]
+    TeamTxBalancer,
+    /// The team random runner approximated by balance-rr
+    #[strum(serialize = "W2NM-TEAM-RUNNER-RANDOM")]
+    TeamRunnerRandom,
+    /// A team tx_hash mapped to the closest bond xmit_hash_policy
+    #[strum(serialize = "W2NM-TEAM-TX-HASH")]
+    TeamTxHash,
+    /// Multiple team link watches where bonds only support one
+    #[strum(serialize = "W2NM-TEAM-LINK-WATCH-MULTIPLE")]
+    TeamLinkWatchMultiple,
+    /// Team link watches with conflicting settings
+    #[strum(serialize = "W2NM-TEAM-LINK-WATCH-MISMATCH")]
+    TeamLinkWatchMismatch,
+    /// A team link watch setting bonds don't support
+    #[strum(serialize = "W2NM-TEAM-LINK-WATCH-UNSUPPORTED")]
+    TeamLinkWatchUnsupported,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_diagnostic_codes() {
+        assert_eq!(Diagnostic::TeamQueueId.to_string(), "W2NM-TEAM-QUEUE-ID");
+        assert_eq!(
+            Diagnostic::from_str("W2NM-UNHANDLED-FIELD").unwrap(),
+            Diagnostic::UnhandledField
+        );
+        assert!(Diagnostic::from_str("W2NM-DOES-NOT-EXIST").is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use crate::diagnostic::Diagnostic;
+use crate::extra::ExtraSettings;
+use crate::report::Findings;
+use serde::{Deserialize, Serialize};
+use serde_with::{skip_serializing_none, DeserializeFromStr, SerializeDisplay};
+use strum_macros::{Display, EnumString};
+
+/// Port types NetworkManager accepts for `ethernet.port`.
+const NM_PORTS: [&str; 5] = ["tp", "aui", "bnc", "mii", "fibre"];
+
+/// Bits of `ethernet.wake-on-lan`
+const NM_WOL_PHY: u32 = 0x2;
+const NM_WOL_UNICAST: u32 = 0x4;
+const NM_WOL_MULTICAST: u32 = 0x8;
+const NM_WOL_BROADCAST: u32 = 0x10;
+const NM_WOL_ARP: u32 = 0x20;
+const NM_WOL_MAGIC: u32 = 0x40;
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+pub struct Ethernet {
+    pub address: Option<String>,
+    #[serde(rename = "autoneg-enable")]
+    pub autoneg: Option<bool>,
+    /// Speed in Mbit/s
+    #[serde(rename = "link-speed")]
+    pub speed: Option<u32>,
+    pub duplex: Option<Duplex>,
+    #[serde(rename = "port-type")]
+    pub port: Option<String>,
+    #[serde(rename = "wake-on-lan")]
+    pub wake_on_lan: Option<WakeOnLan>,
+}
+
+#[derive(Debug, PartialEq, SerializeDisplay, DeserializeFromStr, EnumString, Display, Clone)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Duplex {
+    Half,
+    Full,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeOnLan {
+    /// Wake-on-LAN modes as names (e.g. `magic`) or ethtool letters (e.g. `g`)
+    pub options: String,
+    /// SecureOn password in MAC address format
+    pub sopass: Option<String>,
+}
+
+impl WakeOnLan {
+    /// The `ethernet.wake-on-lan` flags, `None` for unknown modes.
+    ///
+    /// SecureOn (`s`) has no flag, NetworkManager enables it by setting the password.
+    fn nm_flags(&self) -> Option<u32> {
+        let mut flags = 0;
+        for option in self
+            .options
+            .split([',', '|', ' '])
+            .filter(|o| !o.is_empty())
+        {
+            // A single option can also be a string of ethtool letters like `pg`
+            let letters: Vec<String> =
+                if option.len() > 1 && option.chars().all(|c| "pumbagsd".contains(c)) {
+                    option.chars().map(String::from).collect()
+                } else {
+                    vec![option.to_string()]
+                };
+            for letter in letters {
+                flags |= match letter.as_str() {
+                    "p" | "phy" => NM_WOL_PHY,
+                    "u" | "unicast" => NM_WOL_UNICAST,
+                    "m" | "multicast" => NM_WOL_MULTICAST,
+                    "b" | "broadcast" => NM_WOL_BROADCAST,
+                    "a" | "arp" => NM_WOL_ARP,
+                    "g" | "magic" => NM_WOL_MAGIC,
+                    "s" | "secure" | "d" | "disable" => 0,
+                    _ => return None,
+                };
+            }
+        }
+        Some(flags)
+    }
+}
+
+impl Ethernet {
+    /// Sets the link settings as `ethernet` properties.
+    ///
+    /// NetworkManager only forces the link mode with both speed and duplex, without them
+    /// disabling autonegotiation has no effect.
+    pub fn add_link_settings(
+        &self,
+        interface_name: &str,
+        extra: &mut ExtraSettings,
+        findings: &mut Findings,
+    ) {
+        match (self.speed, &self.duplex) {
+            (Some(speed), Some(duplex)) => {
+                extra.set("ethernet", "speed", speed);
+                extra.set("ethernet", "duplex", duplex);
+                if let Some(autoneg) = self.autoneg {
+                    extra.set("ethernet", "auto-negotiate", autoneg);
+                }
+            }
+            (None, None) if self.autoneg != Some(false) => {
+                if let Some(autoneg) = self.autoneg {
+                    extra.set("ethernet", "auto-negotiate", autoneg);
+                }
+            }
+            _ => findings.warn(
+                Diagnostic::EthernetLink,
+                Some("ethernet"),
+                format!(
+                    "NetworkManager needs speed and duplex to set the link mode of {interface_name}, the link settings aren't migrated"
+                ),
+            ),
+        }
+
+        if let Some(port) = &self.port {
+            if NM_PORTS.contains(&port.as_str()) {
+                extra.set("ethernet", "port", port);
+            } else {
+                findings.warn(
+                    Diagnostic::EthernetLink,
+                    Some("ethernet.port"),
+                    format!(
+                        "Port type {port} of {interface_name} isn't supported by NetworkManager"
+                    ),
+                );
+            }
+        }
+
+        if let Some(wake_on_lan) = &self.wake_on_lan {
+            self.add_wake_on_lan(wake_on_lan, interface_name, extra, findings);
+        }
+    }
+
+    fn add_wake_on_lan(
+        &self,
+        wake_on_lan: &WakeOnLan,
+        interface_name: &str,
+        extra: &mut ExtraSettings,
+        findings: &mut Findings,
+    ) {
+        let Some(flags) = wake_on_lan.nm_flags() else {
+            findings.warn(
+                Diagnostic::WakeOnLan,
+                Some("ethernet.wake_on_lan.options"),
+                format!(
+                    "Unknown wake-on-LAN options '{}' of {interface_name}",
+                    wake_on_lan.options
+                ),
+            );
+            return;
+        };
+        extra.set("ethernet", "wake-on-lan", flags);
+        if let Some(sopass) = &wake_on_lan.sopass {
+            if flags & NM_WOL_MAGIC != 0 {
+                extra.set("ethernet", "wake-on-lan-password", sopass);
+            } else {
+                findings.warn(
+                    Diagnostic::WakeOnLan,
+                    Some("ethernet.wake_on_lan.sopass"),
+                    format!(
+                        "NetworkManager only uses the wake-on-LAN password of {interface_name} with magic packets"
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Parses the link settings of `ETHTOOL_OPTIONS` in the `ethtool -s` syntax, other ethtool
+/// commands (e.g. `-K` for offloads) aren't supported.
+pub fn parse_ethtool_options(options: &str) -> Option<Ethernet> {
+    let mut tokens = options.split_whitespace().peekable();
+    if tokens.peek() == Some(&"-s") {
+        // -s <interface>
+        tokens.next();
+        tokens.next();
+    }
+
+    let mut ethernet = Ethernet::default();
+    while let Some(key) = tokens.next() {
+        let value = tokens.next()?;
+        match key {
+            "speed" => ethernet.speed = Some(value.parse().ok()?),
+            "duplex" => ethernet.duplex = Some(value.parse().ok()?),
+            "autoneg" => {
+                ethernet.autoneg = Some(match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return None,
+                })
+            }
+            "port" => ethernet.port = Some(value.to_string()),
+            "wol" => {
+                ethernet
+                    .wake_on_lan
+                    .get_or_insert_with(Default::default)
+                    .options = value.to_string()
+            }
+            "sopass" => {
+                ethernet
+                    .wake_on_lan
+                    .get_or_insert_with(Default::default)
+                    .sopass = Some(value.to_string())
+            }
+            _ => return None,
+        }
+    }
+    Some(ethernet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ethtool_options() {
+        let ethernet =
+            parse_ethtool_options("-s eth0 speed 10000 duplex full autoneg off wol g").unwrap();
+        assert_eq!(
+            ethernet,
+            Ethernet {
+                autoneg: Some(false),
+                speed: Some(10000),
+                duplex: Some(Duplex::Full),
+                wake_on_lan: Some(WakeOnLan {
+                    options: "g".to_string(),
+                    sopass: None,
+                }),
+                ..Default::default()
+            }
+        );
+        assert_eq!(parse_ethtool_options("-K eth0 tso off"), None);
+        assert_eq!(parse_ethtool_options("speed"), None);
+        assert_eq!(parse_ethtool_options("mdix on"), None);
+    }
+
+    #[test]
+    fn test_link_settings() {
+        let ethernet = Ethernet {
+            autoneg: Some(false),
+            speed: Some(10000),
+            duplex: Some(Duplex::Full),
+            port: Some("fibre".to_string()),
+            wake_on_lan: Some(WakeOnLan {
+                options: "pg".to_string(),
+                sopass: Some("01:02:03:04:05:06".to_string()),
+            }),
+            ..Default::default()
+        };
+        let mut extra = ExtraSettings::default();
+        let mut findings = Findings::default();
+        ethernet.add_link_settings("eth0", &mut extra, &mut findings);
+        assert!(!findings.has_warnings());
+        assert_eq!(
+            extra.properties,
+            [
+                ("auto-negotiate", "false"),
+                ("duplex", "full"),
+                ("port", "fibre"),
+                ("speed", "10000"),
+                ("wake-on-lan", "66"),
+                ("wake-on-lan-password", "01:02:03:04:05:06"),
+            ]
+            .into_iter()
+            .map(|(key, value)| (("ethernet".to_string(), key.to_string()), value.to_string()))
+            .collect::<std::collections::BTreeMap<_, _>>()
+        );
+
+        // Forcing the link mode needs both speed and duplex
+        let ethernet = Ethernet {
+            autoneg: Some(false),
+            speed: Some(1000),
+            wake_on_lan: Some(WakeOnLan {
+                options: "u".to_string(),
+                sopass: Some("01:02:03:04:05:06".to_string()),
+            }),
+            ..Default::default()
+        };
+        let mut extra = ExtraSettings::default();
+        let mut findings = Findings::default();
+        ethernet.add_link_settings("eth0", &mut extra, &mut findings);
+        assert_eq!(findings.iter().count(), 2);
+        assert_eq!(
+            extra.properties,
+            [(
+                ("ethernet".to_string(), "wake-on-lan".to_string()),
+                "4".to_string()
+            )]
+            .into_iter()
+            .collect::<std::collections::BTreeMap<_, _>>()
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use agama_network::types::{IpConfig, IpRoute};
+use std::collections::BTreeMap;
+use std::net::Ipv4Addr;
+
+/// Attributes of a single route, e.g. `weight` or `table`.
+pub type RouteAttributes = BTreeMap<String, String>;
+
+/// NetworkManager settings of a connection that the agama model can't express.
+///
+/// They are added to the keyfiles written with `--output-dir` and applied with `nmcli`
+/// after the connections were sent to NetworkManager.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExtraSettings {
+    /// Properties as `(setting, key)` -> value, using the keyfile names and values
+    pub properties: BTreeMap<(String, String), String>,
+    /// Attributes of the routes in `routes4`, by index
+    pub route_attributes4: Vec<RouteAttributes>,
+    /// Attributes of the routes in `routes6`, by index
+    pub route_attributes6: Vec<RouteAttributes>,
+    /// Routing rules in NetworkManager syntax, e.g. `priority 100 from 10.0.0.0/8 table 100`
+    pub routing_rules4: Vec<String>,
+    pub routing_rules6: Vec<String>,
+    /// Kernel settings NetworkManager has no property for, as path below `/proc/sys` -> value
+    pub sysctls: BTreeMap<String, String>,
+    /// Address of the wicked DHCPv4 lease, written into the lease file of the internal client
+    pub lease_address4: Option<Ipv4Addr>,
+}
+
+impl ExtraSettings {
+    pub fn set(&mut self, setting: &str, key: &str, value: impl ToString) {
+        self.properties
+            .insert((setting.to_string(), key.to_string()), value.to_string());
+    }
+
+    pub fn set_sysctl(&mut self, path: String, value: impl ToString) {
+        self.sysctls.insert(path, value.to_string());
+    }
+
+    fn has_route_attributes(&self) -> bool {
+        self.route_attributes4
+            .iter()
+            .chain(&self.route_attributes6)
+            .any(|a| !a.is_empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+            && !self.has_route_attributes()
+            && self.routing_rules4.is_empty()
+            && self.routing_rules6.is_empty()
+            && self.sysctls.is_empty()
+            && self.lease_address4.is_none()
+    }
+
+    /// Arguments for `nmcli connection modify`, routes with attributes are set as a whole.
+    pub fn nmcli_args(&self, ip_config: &IpConfig) -> Vec<String> {
+        let mut args = vec![];
+        for ((setting, key), value) in &self.properties {
+            args.push(format!("{setting}.{key}"));
+            args.push(value.clone());
+        }
+        if self.has_route_attributes() {
+            args.push("ipv4.routes".to_string());
+            args.push(nmcli_routes(&ip_config.routes4, &self.route_attributes4));
+            args.push("ipv6.routes".to_string());
+            args.push(nmcli_routes(&ip_config.routes6, &self.route_attributes6));
+        }
+        if !self.routing_rules4.is_empty() {
+            args.push("ipv4.routing-rules".to_string());
+            args.push(self.routing_rules4.join(", "));
+        }
+        if !self.routing_rules6.is_empty() {
+            args.push("ipv6.routing-rules".to_string());
+            args.push(self.routing_rules6.join(", "));
+        }
+        args
+    }
+}
+
+/// Formats attributes as used by keyfiles (`a=1,b=2`).
+pub fn keyfile_route_options(attributes: &RouteAttributes) -> String {
+    attributes
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn nmcli_routes(routes: &[IpRoute], attributes: &[RouteAttributes]) -> String {
+    routes
+        .iter()
+        .enumerate()
+        .map(|(i, route)| {
+            let mut value = format!(
+                "{}/{}",
+                route.destination.address(),
+                route.destination.network_length()
+            );
+            if let Some(next_hop) = route.next_hop {
+                value.push_str(&format!(" {next_hop}"));
+            }
+            if let Some(metric) = route.metric {
+                value.push_str(&format!(" {metric}"));
+            }
+            for (key, attribute) in attributes.get(i).into_iter().flatten() {
+                value.push_str(&format!(" {key}={attribute}"));
+            }
+            value
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_nmcli_args() {
+        let mut extra = ExtraSettings::default();
+        assert!(extra.is_empty());
+
+        extra.set("ipv6", "addr-gen-mode", "eui64");
+        extra.route_attributes4 = vec![
+            RouteAttributes::new(),
+            RouteAttributes::from([("weight".to_string(), "2".to_string())]),
+        ];
+        let ip_config = IpConfig {
+            routes4: vec![
+                IpRoute {
+                    destination: cidr::IpInet::from_str("10.0.0.0/8").unwrap(),
+                    next_hop: None,
+                    metric: None,
+                },
+                IpRoute {
+                    destination: cidr::IpInet::from_str("0.0.0.0/0").unwrap(),
+                    next_hop: Some("192.168.1.1".parse().unwrap()),
+                    metric: Some(10),
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            extra.nmcli_args(&ip_config),
+            vec![
+                "ipv6.addr-gen-mode",
+                "eui64",
+                "ipv4.routes",
+                "10.0.0.0/8, 0.0.0.0/0 192.168.1.1 10 weight=2",
+                "ipv6.routes",
+                "",
+            ]
+        );
+        assert_eq!(
+            keyfile_route_options(&extra.route_attributes4[1]),
+            "weight=2"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use crate::diagnostic::Diagnostic;
+use crate::interface::Interface;
+use crate::reader::InterfacesResult;
+use crate::report::Findings;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashSet;
+
+/// Selects the interfaces to migrate by name.
+///
+/// Ports of selected controllers and the controllers/parents of selected
+/// interfaces are pulled in automatically so the result stays consistent,
+/// unless they are excluded explicitly.
+#[derive(Debug)]
+pub struct InterfaceFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+fn build_globset(patterns: &[String]) -> Result<GlobSet, anyhow::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Interfaces `interface` can't be migrated without.
+fn dependencies(interface: &Interface) -> Vec<&str> {
+    let mut deps = vec![];
+    if let Some(master) = &interface.link.master {
+        deps.push(master.as_str());
+    }
+    if let Some(vlan) = &interface.vlan {
+        deps.push(vlan.device.as_str());
+    }
+    if let Some(infiniband_child) = &interface.infiniband_child {
+        deps.push(infiniband_child.device.as_str());
+    }
+    if let Some(vlan) = interface.ovs_bridge.as_ref().and_then(|b| b.vlan.as_ref()) {
+        deps.push(vlan.parent.as_str());
+    }
+    deps
+}
+
+impl InterfaceFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, anyhow::Error> {
+        let include = if include.is_empty() {
+            None
+        } else {
+            Some(build_globset(include)?)
+        };
+        Ok(InterfaceFilter {
+            include,
+            exclude: build_globset(exclude)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_none() && self.exclude.is_empty()
+    }
+
+    /// Returns the names of the selected interfaces.
+    ///
+    /// Excluded interfaces stay excluded even when a selected interface needs them, that is
+    /// reported as warning of the selected interface.
+    pub fn select(&self, interfaces: &[Interface], findings: &mut Findings) -> HashSet<String> {
+        let known: HashSet<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        let excluded = |name: &str| self.exclude.is_match(name);
+
+        // (selected interface, excluded interface) pairs already reported
+        let mut warned: HashSet<(&str, &str)> = HashSet::new();
+
+        let mut selected: HashSet<String> = interfaces
+            .iter()
+            .filter(|i| self.include.as_ref().is_none_or(|g| g.is_match(&i.name)))
+            .filter(|i| !excluded(&i.name))
+            .map(|i| i.name.clone())
+            .collect();
+
+        loop {
+            let mut added = vec![];
+            for interface in interfaces {
+                if selected.contains(&interface.name) {
+                    for dep in dependencies(interface) {
+                        if !known.contains(dep) || selected.contains(dep) {
+                            continue;
+                        }
+                        if !excluded(dep) {
+                            added.push(dep.to_string());
+                        } else if warned.insert((interface.name.as_str(), dep)) {
+                            findings.warn_for(
+                                &interface.name,
+                                Diagnostic::ExcludedDependency,
+                                None,
+                                format!(
+                                    "Interface {dep} is excluded but {} depends on it, exclude {} as well",
+                                    interface.name, interface.name
+                                ),
+                            );
+                        }
+                    }
+                } else if let Some(master) = interface
+                    .link
+                    .master
+                    .as_deref()
+                    .filter(|master| selected.contains(*master))
+                {
+                    if !excluded(&interface.name) {
+                        added.push(interface.name.clone());
+                    } else if warned.insert((master, interface.name.as_str())) {
+                        findings.warn_for(
+                            master,
+                            Diagnostic::ExcludedDependency,
+                            None,
+                            format!(
+                                "Port {} of {master} is excluded, {master} is migrated without it",
+                                interface.name
+                            ),
+                        );
+                    }
+                }
+            }
+            if added.is_empty() {
+                break;
+            }
+            for name in added {
+                log::debug!("Selecting interface {name} as dependency");
+                selected.insert(name);
+            }
+        }
+
+        selected
+    }
+
+    /// Drops all interfaces and their findings that aren't selected.
+    pub fn apply(&self, result: &mut InterfacesResult) -> Result<(), anyhow::Error> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let mut findings = Findings::default();
+        let selected = self.select(&result.interfaces, &mut findings);
+        let skipped: HashSet<String> = result
+            .interfaces
+            .iter()
+            .map(|i| i.name.clone())
+            .filter(|name| !selected.contains(name))
+            .collect();
+        for name in &skipped {
+            log::info!("Skipping interface {name}");
+        }
+
+        result.interfaces.retain(|i| selected.contains(&i.name));
+        result.findings.retain(|f| {
+            f.interface
+                .as_ref()
+                .is_none_or(|name| !skipped.contains(name))
+        });
+        result.findings.append(findings);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::Link;
+    use crate::ovs::{OvsBridge, OvsBridgeVlan};
+    use crate::vlan::Vlan;
+
+    fn interface(name: &str, master: Option<&str>) -> Interface {
+        Interface {
+            name: name.to_string(),
+            link: Link {
+                master: master.map(String::from),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn names(selected: HashSet<String>) -> Vec<String> {
+        let mut names: Vec<String> = selected.into_iter().collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_select_pulls_in_dependencies() {
+        let mut vlan = interface("vlan10", None);
+        vlan.vlan = Some(Vlan {
+            device: "bond0".to_string(),
+            address: None,
+            protocol: Default::default(),
+            tag: 10,
+        });
+        let interfaces = vec![
+            interface("eth0", Some("bond0")),
+            interface("eth1", Some("bond0")),
+            interface("bond0", None),
+            interface("eth2", None),
+            vlan,
+        ];
+
+        let mut findings = Findings::default();
+        let filter = InterfaceFilter::new(&["bond0".to_string()], &[]).unwrap();
+        assert_eq!(
+            names(filter.select(&interfaces, &mut findings)),
+            vec!["bond0", "eth0", "eth1"]
+        );
+        assert_eq!(findings.iter().count(), 0);
+
+        // The excluded port eth1 of the pulled in bond0 is reported
+        let filter = InterfaceFilter::new(&["vlan*".to_string()], &["eth1".to_string()]).unwrap();
+        assert_eq!(
+            names(filter.select(&interfaces, &mut findings)),
+            vec!["bond0", "eth0", "vlan10"]
+        );
+        assert!(findings.has_warnings());
+        let warnings: Vec<_> = findings.iter().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, Diagnostic::ExcludedDependency);
+        assert_eq!(warnings[0].interface.as_deref(), Some("bond0"));
+
+        let mut findings = Findings::default();
+        let filter = InterfaceFilter::new(&[], &["eth*".to_string()]).unwrap();
+        assert_eq!(
+            names(filter.select(&interfaces, &mut findings)),
+            vec!["bond0", "vlan10"]
+        );
+        assert_eq!(findings.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_select_ovs_bridge_vlan_parent() {
+        let mut ovs_vlan = interface("ovsbr0.10", None);
+        ovs_vlan.ovs_bridge = Some(OvsBridge {
+            vlan: Some(OvsBridgeVlan {
+                parent: "ovsbr0".to_string(),
+                tag: 10,
+            }),
+        });
+        let interfaces = vec![
+            interface("ovsbr0", None),
+            interface("eth0", Some("ovsbr0")),
+            ovs_vlan,
+        ];
+
+        let mut findings = Findings::default();
+        let filter = InterfaceFilter::new(&["ovsbr0.10".to_string()], &[]).unwrap();
+        assert_eq!(
+            names(filter.select(&interfaces, &mut findings)),
+            vec!["eth0", "ovsbr0", "ovsbr0.10"]
+        );
+        assert!(!findings.has_warnings());
+    }
+
+    #[test]
+    fn test_select_excluded_parent_warns() {
+        let interfaces = vec![
+            interface("eth0", Some("bond0")),
+            interface("bond0", None),
+            interface("eth2", None),
+        ];
+
+        let mut findings = Findings::default();
+        let filter = InterfaceFilter::new(&[], &["bond0".to_string()]).unwrap();
+        assert_eq!(
+            names(filter.select(&interfaces, &mut findings)),
+            vec!["eth0", "eth2"]
+        );
+        let warnings: Vec<_> = findings.iter().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].interface.as_deref(), Some("eth0"));
+        assert!(warnings[0].message.contains("eth0 depends on it"));
+
+        let mut findings = Findings::default();
+        let filter = InterfaceFilter::new(&[], &["bond0".to_string(), "eth0".to_string()]).unwrap();
+        assert_eq!(
+            names(filter.select(&interfaces, &mut findings)),
+            vec!["eth2"]
+        );
+        assert!(!findings.has_warnings());
+    }
+}
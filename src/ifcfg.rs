@@ -0,0 +1,1242 @@
+use crate::bond::*;
+use crate::bridge::Bridge;
+use crate::diagnostic::Diagnostic;
+use crate::ethernet::{parse_ethtool_options, Ethernet};
+use crate::interface::*;
+use crate::reader::{is_sysconfig_file, list_files, InterfacesResult};
+use crate::report::Findings;
+use crate::tuntap::{Tap, Tun};
+use crate::vlan::{Vlan, WickedVlanProtocol};
+use cidr::IpInet;
+use std::collections::BTreeMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// Variables that don't have any effect on the migration.
+// The list must be in alphabetical order.
+const IGNORED_VARIABLES: &[&str] = &["FIREWALL", "NAME", "NETWORK", "USERCONTROL"];
+
+/// Variables of a single sysconfig file, variables are removed once they are handled.
+#[derive(Debug, Default)]
+struct Variables(BTreeMap<String, String>);
+
+impl Variables {
+    fn take(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    /// Takes all variables starting with `prefix` and returns them with their suffix.
+    fn take_prefixed(&mut self, prefix: &str) -> Vec<(String, String)> {
+        let keys: Vec<String> = self
+            .0
+            .keys()
+            .filter(|k| k.starts_with(prefix))
+            .cloned()
+            .collect();
+        keys.into_iter()
+            .filter_map(|k| {
+                let value = self.0.remove(&k)?;
+                Some((k[prefix.len()..].to_string(), value))
+            })
+            .collect()
+    }
+
+    fn take_yes(&mut self, key: &str) -> Option<bool> {
+        self.take(key)
+            .map(|v| matches!(v.to_lowercase().as_str(), "yes" | "on" | "true" | "1"))
+    }
+}
+
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        value[1..value.len() - 1].to_string()
+    } else if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        value[1..value.len() - 1].replace("\\\"", "\"")
+    } else {
+        // Unquoted values end at the first comment
+        value
+            .split_once(" #")
+            .map(|(v, _)| v)
+            .unwrap_or(value)
+            .trim()
+            .to_string()
+    }
+}
+
+/// Parses the shell variable assignments of a sysconfig file, empty values are skipped.
+fn parse_sysconfig(contents: &str) -> Variables {
+    let mut vars = Variables::default();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = unquote(value.trim());
+        if !value.is_empty() {
+            vars.0.insert(key.trim().to_string(), value);
+        }
+    }
+    vars
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct RouteEntry {
+    destination: Option<String>,
+    gateway: Option<String>,
+    interface: Option<String>,
+    metric: Option<u32>,
+    pref_source: Option<String>,
+    kern: RouteKern,
+    metrics: RouteMetrics,
+    onlink: bool,
+}
+
+fn netmask_to_prefix(netmask: &str) -> Option<u8> {
+    if let Ok(prefix) = netmask.parse::<u8>() {
+        return Some(prefix);
+    }
+    let netmask = Ipv4Addr::from_str(netmask).ok()?;
+    let bits = u32::from(netmask);
+    if bits.leading_ones() + bits.trailing_zeros() != 32 {
+        return None;
+    }
+    Some(bits.leading_ones() as u8)
+}
+
+fn non_empty(column: Option<&str>) -> Option<&str> {
+    column.filter(|c| *c != "-")
+}
+
+/// Parses an `ifroute-*` or `routes` file, `interface` is used for routes without a device.
+fn parse_routes(
+    contents: &str,
+    interface: Option<&str>,
+    origin: &str,
+    findings: &mut Findings,
+) -> Vec<RouteEntry> {
+    let mut routes = vec![];
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut columns = line.split_whitespace();
+        let destination = columns.next();
+        let gateway = non_empty(columns.next());
+        let netmask = non_empty(columns.next());
+        let device = non_empty(columns.next()).or(interface);
+
+        let destination = match destination {
+            None | Some("default") | Some("-") => None,
+            Some(destination) if destination.contains('/') => Some(destination.to_string()),
+            Some(destination) => match netmask.map(netmask_to_prefix) {
+                Some(Some(prefix)) => Some(format!("{destination}/{prefix}")),
+                Some(None) => {
+                    findings.warn(
+                        Diagnostic::InvalidRoute,
+                        Some("routes"),
+                        format!("Invalid netmask in route \"{line}\" of {origin}"),
+                    );
+                    continue;
+                }
+                None => Some(destination.to_string()),
+            },
+        };
+
+        let mut route = RouteEntry {
+            destination,
+            gateway: gateway.map(String::from),
+            interface: device.map(String::from),
+            ..Default::default()
+        };
+        let options: Vec<&str> = columns.collect();
+        let mut options = options.iter();
+        while let Some(option) = options.next() {
+            let mut value = || options.next().map(|v| v.to_string());
+            match *option {
+                "metric" | "preference" => {
+                    route.metric = value().and_then(|m| m.parse::<u32>().ok());
+                }
+                "table" => route.kern.table = value(),
+                "scope" => route.kern.scope = value(),
+                "protocol" | "proto" => route.kern.protocol = value(),
+                "src" => route.pref_source = value(),
+                "mtu" => route.metrics.mtu = value().and_then(|v| v.parse().ok()),
+                "window" => route.metrics.window = value().and_then(|v| v.parse().ok()),
+                "initcwnd" => route.metrics.initcwnd = value().and_then(|v| v.parse().ok()),
+                "onlink" => route.onlink = true,
+                // Route type column
+                "unicast" | "local" | "blackhole" | "unreachable" | "prohibit" | "throw" => {
+                    route.kern.route_type = Some(option.to_string());
+                }
+                option => {
+                    findings.warn(
+                        Diagnostic::UnhandledField,
+                        Some("routes"),
+                        format!("Unhandled route option \"{option}\" in \"{line}\" of {origin}"),
+                    );
+                }
+            }
+        }
+
+        routes.push(route);
+    }
+    routes
+}
+
+/// Parses an `ifrule-*` file, every line holds the arguments of `ip rule`, optionally prefixed
+/// with `ipv4` or `ipv6`. Returns the rules with a flag whether they are ipv6 rules.
+fn parse_rules(contents: &str, origin: &str, findings: &mut Findings) -> Vec<(bool, Rule)> {
+    let mut rules = vec![];
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut ipv6 = None;
+        let mut rule = Rule::default();
+        let mut words = line.split_whitespace();
+        while let Some(word) = words.next() {
+            let mut value = || words.next().map(String::from);
+            match word {
+                "ipv4" | "-4" => ipv6 = Some(false),
+                "ipv6" | "-6" => ipv6 = Some(true),
+                "not" => rule.selector.invert = true,
+                "priority" | "pref" | "preference" => {
+                    rule.priority = value().and_then(|p| p.parse().ok());
+                }
+                "from" => rule.selector.from = value(),
+                "to" => rule.selector.to = value(),
+                "tos" | "dsfield" => rule.selector.tos = value(),
+                "fwmark" => {
+                    if let Some(fwmark) = value() {
+                        let (mark, mask) = match fwmark.split_once('/') {
+                            Some((mark, mask)) => (mark.to_string(), Some(mask.to_string())),
+                            None => (fwmark, None),
+                        };
+                        rule.selector.fwmark = Some(mark);
+                        rule.selector.fwmask = mask;
+                    }
+                }
+                "iif" | "dev" => rule.selector.iif = value(),
+                "oif" => rule.selector.oif = value(),
+                "table" | "lookup" => rule.action.table = value(),
+                "blackhole" | "unreachable" | "prohibit" => {
+                    rule.action.action_type = Some(word.to_string());
+                }
+                word => {
+                    findings.warn(
+                        Diagnostic::UnhandledField,
+                        Some("rules"),
+                        format!("Unhandled rule option \"{word}\" in \"{line}\" of {origin}"),
+                    );
+                }
+            }
+        }
+        let ipv6 = ipv6.unwrap_or_else(|| {
+            [&rule.selector.from, &rule.selector.to]
+                .into_iter()
+                .flatten()
+                .any(|prefix| prefix.contains(':'))
+        });
+        rules.push((ipv6, rule));
+    }
+    rules
+}
+
+fn parse_start_mode(start_mode: &str) -> Option<ControlMode> {
+    match start_mode {
+        "auto" | "boot" | "onboot" | "on" | "nfsroot" => Some(ControlMode::Boot),
+        "hotplug" | "ifplugd" => Some(ControlMode::Hotplug),
+        "manual" => Some(ControlMode::Manual),
+        "off" => Some(ControlMode::Off),
+        _ => None,
+    }
+}
+
+fn parse_bond_mode(mode: &str) -> Option<WickedBondMode> {
+    match mode {
+        "0" | "balance-rr" => Some(WickedBondMode::BalanceRr),
+        "1" | "active-backup" => Some(WickedBondMode::ActiveBackup),
+        "2" | "balance-xor" => Some(WickedBondMode::BalanceXor),
+        "3" | "broadcast" => Some(WickedBondMode::Broadcast),
+        "4" | "802.3ad" => Some(WickedBondMode::IEEE8023ad),
+        "5" | "balance-tlb" => Some(WickedBondMode::BalanceTlb),
+        "6" | "balance-alb" => Some(WickedBondMode::BalanceAlb),
+        _ => None,
+    }
+}
+
+fn parse_xmit_hash_policy(policy: &str) -> Option<XmitHashPolicy> {
+    match policy {
+        "layer2" => Some(XmitHashPolicy::Layer2),
+        "layer2+3" => Some(XmitHashPolicy::Layer23),
+        "layer3+4" => Some(XmitHashPolicy::Layer34),
+        "encap2+3" => Some(XmitHashPolicy::Encap23),
+        "encap3+4" => Some(XmitHashPolicy::Encap34),
+        _ => None,
+    }
+}
+
+/// Converts `BONDING_MODULE_OPTS` into a bond.
+fn parse_bond_options(options: &str, findings: &mut Findings) -> Bond {
+    let mut bond = Bond {
+        mode: WickedBondMode::BalanceRr,
+        miimon: None,
+        arpmon: None,
+        xmit_hash_policy: None,
+        packets_per_slave: None,
+        tlb_dynamic_lb: None,
+        lacp_rate: None,
+        ad_select: None,
+        ad_user_port_key: None,
+        ad_actor_sys_prio: None,
+        ad_actor_system: None,
+        min_links: None,
+        primary_reselect: None,
+        fail_over_mac: None,
+        num_grat_arp: None,
+        num_unsol_na: None,
+        lp_interval: None,
+        primary: None,
+        resend_igmp: None,
+        all_slaves_active: None,
+        address: None,
+    };
+    let mut miimon = Miimon::default();
+    let mut arp_interval = None;
+    let mut arp_validate = ArpValidate::None;
+    let mut arp_all_targets = None;
+    let mut arp_targets = vec![];
+
+    for option in options.split_whitespace() {
+        let Some((key, value)) = option.split_once('=') else {
+            continue;
+        };
+        let number = value.parse::<u32>().ok();
+        let handled = match key {
+            "mode" => parse_bond_mode(value)
+                .map(|mode| bond.mode = mode)
+                .is_some(),
+            "miimon" => number.map(|n| miimon.frequency = n).is_some(),
+            "updelay" => number.map(|n| miimon.updelay = Some(n)).is_some(),
+            "downdelay" => number.map(|n| miimon.downdelay = Some(n)).is_some(),
+            "use_carrier" => {
+                if value == "0" {
+                    miimon.carrier_detect = CarrierDetect::Ioctl;
+                }
+                true
+            }
+            "arp_interval" => number.map(|n| arp_interval = Some(n)).is_some(),
+            "arp_ip_target" => {
+                arp_targets.extend(value.split(',').map(String::from));
+                true
+            }
+            "arp_validate" => ArpValidate::from_str(value)
+                .map(|v| arp_validate = v)
+                .is_ok(),
+            "arp_all_targets" => ArpValidateTargets::from_str(value)
+                .map(|v| arp_all_targets = Some(v))
+                .is_ok(),
+            "xmit_hash_policy" => parse_xmit_hash_policy(value)
+                .map(|p| bond.xmit_hash_policy = Some(p))
+                .is_some(),
+            "packets_per_slave" => number.map(|n| bond.packets_per_slave = Some(n)).is_some(),
+            "tlb_dynamic_lb" => {
+                bond.tlb_dynamic_lb = Some(value == "1");
+                true
+            }
+            "lacp_rate" => LacpRate::from_str(value)
+                .map(|r| bond.lacp_rate = Some(r))
+                .is_ok(),
+            "ad_select" => AdSelect::from_str(value)
+                .map(|s| bond.ad_select = Some(s))
+                .is_ok(),
+            "ad_user_port_key" => number.map(|n| bond.ad_user_port_key = Some(n)).is_some(),
+            "ad_actor_sys_prio" => number.map(|n| bond.ad_actor_sys_prio = Some(n)).is_some(),
+            "ad_actor_system" => {
+                bond.ad_actor_system = Some(value.to_string());
+                true
+            }
+            "min_links" => number.map(|n| bond.min_links = Some(n)).is_some(),
+            "primary_reselect" => PrimaryReselect::from_str(value)
+                .map(|p| bond.primary_reselect = Some(p))
+                .is_ok(),
+            "fail_over_mac" => FailOverMac::from_str(value)
+                .map(|f| bond.fail_over_mac = Some(f))
+                .is_ok(),
+            "num_grat_arp" => number.map(|n| bond.num_grat_arp = Some(n)).is_some(),
+            "num_unsol_na" => number.map(|n| bond.num_unsol_na = Some(n)).is_some(),
+            "lp_interval" => number.map(|n| bond.lp_interval = Some(n)).is_some(),
+            "primary" => {
+                bond.primary = Some(value.to_string());
+                true
+            }
+            "resend_igmp" => number.map(|n| bond.resend_igmp = Some(n)).is_some(),
+            "all_slaves_active" => {
+                bond.all_slaves_active = Some(value == "1");
+                true
+            }
+            _ => false,
+        };
+        if !handled {
+            findings.warn(
+                Diagnostic::UnhandledField,
+                Some("BONDING_MODULE_OPTS"),
+                format!("Unhandled bonding option \"{option}\""),
+            );
+        }
+    }
+
+    if miimon.frequency > 0 {
+        bond.miimon = Some(miimon);
+    }
+    if let Some(interval) = arp_interval.filter(|i| *i > 0) {
+        bond.arpmon = Some(ArpMon {
+            interval,
+            validate: arp_validate,
+            validate_targets: arp_all_targets,
+            targets: arp_targets,
+        });
+    }
+    bond
+}
+
+/// Port interface for a device that is only referenced by a controller.
+fn port_interface(name: &str, origin: &str) -> Interface {
+    Interface {
+        name: name.to_string(),
+        origin: origin.to_string(),
+        control: Control {
+            mode: ControlMode::Hotplug,
+            ..Default::default()
+        },
+        ipv4: Ipv4 {
+            enabled: false,
+            ..Default::default()
+        },
+        ipv6: Ipv6 {
+            enabled: false,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+struct Ifcfg {
+    name: String,
+    origin: String,
+    vars: Variables,
+}
+
+/// Ports of a bond or bridge, with their port options.
+type Ports = Vec<(String, LinkPort)>;
+
+impl Ifcfg {
+    fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Couldn't read {}: {}", path.display(), e))?;
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        Ok(Ifcfg {
+            name: file_name.trim_start_matches("ifcfg-").to_string(),
+            origin: format!("compat:suse:{}", path.display()),
+            vars: parse_sysconfig(&contents),
+        })
+    }
+
+    fn invalid_value(&self, findings: &mut Findings, key: &str, value: &str) {
+        findings.warn(
+            Diagnostic::IfcfgInvalidValue,
+            Some(key),
+            format!(
+                "Invalid value \"{value}\" for {key} in interface {}",
+                self.name
+            ),
+        );
+    }
+
+    fn take_parsed<T: FromStr>(&mut self, key: &str, findings: &mut Findings) -> Option<T> {
+        let value = self.vars.take(key)?;
+        match value.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.invalid_value(findings, key, &value);
+                None
+            }
+        }
+    }
+
+    /// The FQDN option settings of `DHCLIENT` or `DHCLIENT6`, if any is set.
+    fn fqdn(&mut self, prefix: &str, findings: &mut Findings) -> Option<DhcpFqdn> {
+        let enabled = self.take_parsed(&format!("{prefix}_FQDN_ENABLED"), findings);
+        let update = self.take_parsed(&format!("{prefix}_FQDN_UPDATE"), findings);
+        let encode = self.vars.take_yes(&format!("{prefix}_FQDN_ENCODE"));
+        let qualify = self.vars.take_yes(&format!("{prefix}_FQDN_QUALIFY"));
+        if enabled.is_none() && update.is_none() && encode.is_none() && qualify.is_none() {
+            return None;
+        }
+        let default = DhcpFqdn::default();
+        Some(DhcpFqdn {
+            enabled: enabled.unwrap_or(default.enabled),
+            update: update.unwrap_or(default.update),
+            encode: encode.unwrap_or(default.encode),
+            qualify: qualify.unwrap_or(default.qualify),
+        })
+    }
+
+    fn addresses(&mut self, findings: &mut Findings) -> (Vec<Address>, Vec<Address>) {
+        let (mut addresses4, mut addresses6) = (vec![], vec![]);
+        for (suffix, ipaddr) in self.vars.take_prefixed("IPADDR") {
+            let prefix = self.vars.take(&format!("PREFIXLEN{suffix}"));
+            let netmask = self.vars.take(&format!("NETMASK{suffix}"));
+            let broadcast = self.vars.take(&format!("BROADCAST{suffix}"));
+            self.vars.take(&format!("LABEL{suffix}"));
+
+            let prefix = prefix.or_else(|| {
+                netmask
+                    .as_deref()
+                    .and_then(netmask_to_prefix)
+                    .map(|p| p.to_string())
+            });
+            let local = match prefix {
+                Some(prefix) if !ipaddr.contains('/') => format!("{ipaddr}/{prefix}"),
+                _ => ipaddr.clone(),
+            };
+            let Ok(inet) = IpInet::from_str(&local) else {
+                self.invalid_value(findings, &format!("IPADDR{suffix}"), &ipaddr);
+                continue;
+            };
+            let address = Address {
+                local: inet.to_string(),
+                broadcast,
+            };
+            if inet.is_ipv4() {
+                addresses4.push(address);
+            } else {
+                addresses6.push(address);
+            }
+        }
+        (addresses4, addresses6)
+    }
+
+    /// Converts the ifcfg into an interface and returns the ports it controls.
+    fn to_interface(
+        &mut self,
+        findings: &mut Findings,
+    ) -> Result<(Interface, Ports), anyhow::Error> {
+        let mut interface = Interface {
+            name: self.name.clone(),
+            origin: self.origin.clone(),
+            ..Default::default()
+        };
+        let mut ports: Ports = vec![];
+
+        if let Some(start_mode) = self.vars.take("STARTMODE") {
+            match parse_start_mode(&start_mode) {
+                Some(mode) => interface.control.mode = mode,
+                None => self.invalid_value(findings, "STARTMODE", &start_mode),
+            }
+        }
+        interface.link.mtu = self.take_parsed("MTU", findings);
+        interface.firewall.zone = self.vars.take("ZONE");
+        let lladdr = self.vars.take("LLADDR");
+        let interface_type = self.vars.take("INTERFACETYPE");
+
+        if self.vars.take_yes("BONDING_MASTER") == Some(true) {
+            let options = self.vars.take("BONDING_MODULE_OPTS").unwrap_or_default();
+            let mut bond = parse_bond_options(&options, findings);
+            bond.address = lladdr;
+            interface.bond = Some(bond);
+            for (_, port) in self.vars.take_prefixed("BONDING_SLAVE") {
+                ports.push((
+                    port,
+                    LinkPort {
+                        port_type: LinkPortType::Bond,
+                        priority: None,
+                        path_cost: None,
+                        queue_id: None,
+                        prio: None,
+                        sticky: false,
+                        lacp_key: None,
+                        lacp_prio: None,
+                    },
+                ));
+            }
+        } else if self.vars.take_yes("BRIDGE") == Some(true) {
+            interface.bridge = Some(Bridge {
+                stp: self.vars.take_yes("BRIDGE_STP").unwrap_or(false),
+                priority: self.take_parsed("BRIDGE_PRIORITY", findings),
+                forward_delay: self.take_parsed("BRIDGE_FORWARDDELAY", findings),
+                hello_time: self.take_parsed("BRIDGE_HELLOTIME", findings),
+                max_age: self.take_parsed("BRIDGE_MAXAGE", findings),
+                aging_time: self.take_parsed("BRIDGE_AGEINGTIME", findings),
+                address: lladdr,
+            });
+
+            let priorities = self.vars.take("BRIDGE_PORTPRIORITIES").unwrap_or_default();
+            let path_costs = self.vars.take("BRIDGE_PATHCOSTS").unwrap_or_default();
+            let mut priorities = priorities.split_whitespace();
+            let mut path_costs = path_costs.split_whitespace();
+            for port in self
+                .vars
+                .take("BRIDGE_PORTS")
+                .unwrap_or_default()
+                .split_whitespace()
+            {
+                ports.push((
+                    port.to_string(),
+                    LinkPort {
+                        port_type: LinkPortType::Bridge,
+                        priority: priorities.next().and_then(|p| p.parse().ok()),
+                        path_cost: path_costs.next().and_then(|p| p.parse().ok()),
+                        queue_id: None,
+                        prio: None,
+                        sticky: false,
+                        lacp_key: None,
+                        lacp_prio: None,
+                    },
+                ));
+            }
+        } else if let Some(device) = self.vars.take("ETHERDEVICE") {
+            let tag = self
+                .vars
+                .take("VLAN_ID")
+                .or_else(|| {
+                    self.name
+                        .rsplit_once('.')
+                        .map(|(_, id)| id.to_string())
+                        .or_else(|| self.name.strip_prefix("vlan").map(String::from))
+                })
+                .and_then(|id| id.parse().ok());
+            let Some(tag) = tag else {
+                anyhow::bail!("Missing VLAN_ID for vlan interface {}", self.name);
+            };
+            let protocol = match self.vars.take("VLAN_PROTOCOL").as_deref() {
+                Some("ieee802-1ad") => WickedVlanProtocol::Ieee802Ad,
+                _ => WickedVlanProtocol::Ieee802_1Q,
+            };
+            interface.vlan = Some(Vlan {
+                device,
+                address: lladdr,
+                protocol,
+                tag,
+            });
+        } else if let Some(tunnel) = self.vars.take("TUNNEL") {
+            let owner = self.vars.take("TUNNEL_SET_OWNER");
+            let group = self.vars.take("TUNNEL_SET_GROUP");
+            let (owner, group) = (
+                owner.as_deref().and_then(|o| o.parse().ok()),
+                group.as_deref().and_then(|g| g.parse().ok()),
+            );
+            match tunnel.as_str() {
+                "tap" => interface.tap = Some(Tap { owner, group }),
+                "tun" => interface.tun = Some(Tun { owner, group }),
+                _ => anyhow::bail!("Unsupported TUNNEL \"{tunnel}\" in interface {}", self.name),
+            }
+        } else if interface_type.as_deref() == Some("dummy") || self.name.starts_with("dummy") {
+            interface.dummy = Some(Dummy { address: lladdr });
+        } else {
+            let mut ethernet = Ethernet::default();
+            // Other ethtool commands are left as unhandled variable
+            if let Some(settings) = self
+                .vars
+                .0
+                .get("ETHTOOL_OPTIONS")
+                .and_then(|options| parse_ethtool_options(options))
+            {
+                self.vars.take("ETHTOOL_OPTIONS");
+                ethernet = settings;
+            }
+            ethernet.address = lladdr;
+            interface.ethernet = Some(ethernet);
+        }
+
+        let bootproto = self
+            .vars
+            .take("BOOTPROTO")
+            .unwrap_or_else(|| "static".to_string())
+            .to_lowercase();
+        let (addresses4, addresses6) = self.addresses(findings);
+
+        if bootproto == "none" {
+            interface.ipv4.enabled = false;
+            interface.ipv6.enabled = false;
+        } else {
+            for method in bootproto.split('+') {
+                match method {
+                    "static" => {}
+                    "dhcp" => {
+                        interface.ipv4_dhcp = Some(Ipv4Dhcp::default());
+                        interface.ipv6_dhcp = Some(Ipv6Dhcp::default());
+                    }
+                    "dhcp4" => interface.ipv4_dhcp = Some(Ipv4Dhcp::default()),
+                    "dhcp6" => interface.ipv6_dhcp = Some(Ipv6Dhcp::default()),
+                    "autoip" => {
+                        // Combined with dhcp, autoip is only used as fallback
+                        let fallback = bootproto.contains("dhcp");
+                        interface.ipv4_auto = Some(Ipv4Auto {
+                            enabled: true,
+                            flags: fallback.then(|| "fallback".to_string()),
+                        })
+                    }
+                    "auto6" => interface.ipv6_auto = Some(Ipv6Auto::default()),
+                    _ => self.invalid_value(findings, "BOOTPROTO", &bootproto),
+                }
+            }
+            if !addresses4.is_empty() {
+                interface.ipv4_static = Some(Ipv4Static {
+                    addresses: Some(addresses4),
+                    routes: None,
+                });
+            }
+            if !addresses6.is_empty() {
+                interface.ipv6_static = Some(Ipv6Static {
+                    addresses: Some(addresses6),
+                    routes: None,
+                });
+            }
+        }
+
+        if let Some(ipv4_dhcp) = &mut interface.ipv4_dhcp {
+            ipv4_dhcp.client_id = self.vars.take("DHCLIENT_CLIENT_ID");
+            ipv4_dhcp.hostname = self
+                .vars
+                .take("DHCLIENT_HOSTNAME_OPTION")
+                .filter(|h| h != "AUTO");
+            if let Some(release) = self.vars.take_yes("DHCLIENT_RELEASE_BEFORE_QUIT") {
+                ipv4_dhcp.release_lease = release;
+            }
+            ipv4_dhcp.fqdn = self.fqdn("DHCLIENT", findings);
+            ipv4_dhcp.vendor_class = self.vars.take("DHCLIENT_VENDOR_CLASS_ID");
+            if let Some(user_class) = self.vars.take("DHCLIENT_USER_CLASS_ID") {
+                ipv4_dhcp.user_class = Some(UserClass {
+                    format: self.vars.take("DHCLIENT_USER_CLASS_FORMAT"),
+                    identifiers: vec![user_class],
+                });
+            }
+            ipv4_dhcp.lease_time = self.take_parsed("DHCLIENT_LEASE_TIME", findings);
+        }
+        if let Some(ipv6_dhcp) = &mut interface.ipv6_dhcp {
+            ipv6_dhcp.hostname = self
+                .vars
+                .take("DHCLIENT6_HOSTNAME_OPTION")
+                .filter(|h| h != "AUTO");
+            if let Some(release) = self.vars.take_yes("DHCLIENT6_RELEASE_BEFORE_QUIT") {
+                ipv6_dhcp.release_lease = release;
+            }
+            if let Some(mode) = self.vars.take("DHCLIENT6_MODE") {
+                ipv6_dhcp.mode = mode;
+            }
+            if let Some(rapid_commit) = self.vars.take_yes("DHCLIENT6_RAPID_COMMIT") {
+                ipv6_dhcp.rapid_commit = rapid_commit;
+            }
+            ipv6_dhcp.prefix_hint = self.vars.take("DHCLIENT6_PREFIX_HINT");
+            ipv6_dhcp.fqdn = self.fqdn("DHCLIENT6", findings);
+            let enterprise_number = self.take_parsed("DHCLIENT6_VENDOR_CLASS_EN", findings);
+            let vendor_data = self.vars.take("DHCLIENT6_VENDOR_CLASS_DATA");
+            if enterprise_number.is_some() || vendor_data.is_some() {
+                ipv6_dhcp.vendor_class = Some(VendorClass6 {
+                    enterprise_number,
+                    data: vendor_data.into_iter().collect(),
+                });
+            }
+            if let Some(user_class) = self.vars.take("DHCLIENT6_USER_CLASS_DATA") {
+                ipv6_dhcp.user_class = Some(UserClass {
+                    format: None,
+                    identifiers: vec![user_class],
+                });
+            }
+            ipv6_dhcp.lease_time = self.take_parsed("DHCLIENT6_LEASE_TIME", findings);
+        }
+
+        let remaining: Vec<String> = self.vars.0.keys().cloned().collect();
+        for key in remaining {
+            if IGNORED_VARIABLES.binary_search(&key.as_str()).is_err() {
+                findings.warn(
+                    Diagnostic::UnhandledField,
+                    Some(key.as_str()),
+                    format!("Unhandled field in interface {}: {key}", self.name),
+                );
+            }
+        }
+
+        Ok((interface, ports))
+    }
+}
+
+/// Finds the interface with an address in the subnet of the route's gateway.
+fn route_interface(gateway: IpAddr, interfaces: &[Interface]) -> Option<usize> {
+    interfaces.iter().position(|interface| {
+        let addresses4 = interface
+            .ipv4_static
+            .as_ref()
+            .and_then(|s| s.addresses.as_ref());
+        let addresses6 = interface
+            .ipv6_static
+            .as_ref()
+            .and_then(|s| s.addresses.as_ref());
+        addresses4
+            .into_iter()
+            .chain(addresses6)
+            .flatten()
+            .filter_map(|address| IpInet::from_str(&address.local).ok())
+            .any(|inet| inet.network().contains(&gateway))
+    })
+}
+
+fn add_route(
+    interfaces: &mut [Interface],
+    route: RouteEntry,
+    findings: &mut Findings,
+) -> Result<(), anyhow::Error> {
+    let Some(gateway) = route.gateway else {
+        findings.warn(
+            Diagnostic::InvalidRoute,
+            Some("routes"),
+            format!(
+                "Route to {} without gateway isn't supported",
+                route.destination.as_deref().unwrap_or("default")
+            ),
+        );
+        return Ok(());
+    };
+    let gateway_addr = IpAddr::from_str(&gateway)?;
+
+    let index = match &route.interface {
+        Some(name) => interfaces.iter().position(|i| &i.name == name),
+        None => route_interface(gateway_addr, interfaces),
+    };
+    let Some(index) = index else {
+        findings.warn(
+            Diagnostic::InvalidRoute,
+            Some("routes"),
+            format!(
+                "Couldn't find the interface for route to {} via {gateway}",
+                route.destination.as_deref().unwrap_or("default"),
+            ),
+        );
+        return Ok(());
+    };
+
+    let interface = &mut interfaces[index];
+    let wicked_route = Route {
+        destination: route.destination,
+        nexthops: Some(vec![Nexthop {
+            gateway,
+            flags: route.onlink.then(|| "onlink".to_string()),
+            ..Default::default()
+        }]),
+        priority: route.metric,
+        pref_source: route.pref_source,
+        kern: (route.kern != RouteKern::default()).then_some(route.kern),
+        metrics: (route.metrics != RouteMetrics::default()).then_some(route.metrics),
+    };
+    let routes = if gateway_addr.is_ipv4() {
+        &mut interface
+            .ipv4_static
+            .get_or_insert(Ipv4Static {
+                addresses: None,
+                routes: None,
+            })
+            .routes
+    } else {
+        &mut interface
+            .ipv6_static
+            .get_or_insert(Ipv6Static {
+                addresses: None,
+                routes: None,
+            })
+            .routes
+    };
+    routes.get_or_insert_with(Vec::new).push(wicked_route);
+    Ok(())
+}
+
+/// Returns the ifcfg, route and rule files of a file or directory.
+type SysconfigFiles = (Vec<PathBuf>, Vec<PathBuf>, Vec<PathBuf>);
+
+fn ifcfg_files(path: &Path) -> Result<SysconfigFiles, anyhow::Error> {
+    if path.is_dir() {
+        let files = list_files(path, false)?;
+        let ifcfgs = files
+            .iter()
+            .filter(|f| is_sysconfig_file(f, "ifcfg-"))
+            .filter(|f| f.file_name().is_some_and(|n| n != "ifcfg-lo"))
+            .cloned()
+            .collect();
+        let routes = files
+            .iter()
+            .filter(|f| {
+                is_sysconfig_file(f, "ifroute-") || f.file_name().is_some_and(|n| n == "routes")
+            })
+            .cloned()
+            .collect();
+        let rules = files
+            .iter()
+            .filter(|f| is_sysconfig_file(f, "ifrule-"))
+            .cloned()
+            .collect();
+        Ok((ifcfgs, routes, rules))
+    } else {
+        Ok((vec![path.to_path_buf()], vec![], vec![]))
+    }
+}
+
+/// Reads `ifcfg-*`, `ifroute-*`, `ifrule-*` and `routes` files from the given files or directories.
+pub fn read_ifcfg(paths: &[PathBuf]) -> Result<InterfacesResult, anyhow::Error> {
+    let mut result = InterfacesResult {
+        interfaces: vec![],
+        netconfig: None,
+        netconfig_dhcp: None,
+        wicked_state: None,
+        findings: Findings::default(),
+    };
+
+    let mut ifcfgs = vec![];
+    let mut routes = vec![];
+    let mut rules = vec![];
+    for path in paths {
+        let (ifcfg_paths, route_paths, rule_paths) = ifcfg_files(path)?;
+        for ifcfg_path in ifcfg_paths {
+            ifcfgs.push(Ifcfg::read(&ifcfg_path)?);
+        }
+        for route_path in route_paths {
+            let contents = fs::read_to_string(&route_path)?;
+            let file_name = route_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            let interface = file_name.strip_prefix("ifroute-");
+            routes.append(&mut parse_routes(
+                &contents,
+                interface,
+                &route_path.display().to_string(),
+                &mut result.findings,
+            ));
+        }
+        for rule_path in rule_paths {
+            let contents = fs::read_to_string(&rule_path)?;
+            let interface = rule_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix("ifrule-"))
+                .unwrap_or_default()
+                .to_string();
+            for (ipv6, rule) in parse_rules(
+                &contents,
+                &rule_path.display().to_string(),
+                &mut result.findings,
+            ) {
+                rules.push((interface.clone(), ipv6, rule));
+            }
+        }
+    }
+    ifcfgs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut all_ports = vec![];
+    for ifcfg in &mut ifcfgs {
+        let mut findings = Findings::default();
+        let (interface, ports) = ifcfg.to_interface(&mut findings)?;
+        findings.set_interface(&interface.name);
+        result.findings.append(findings);
+        for (port, link_port) in ports {
+            all_ports.push((port, interface.name.clone(), link_port));
+        }
+        result.interfaces.push(interface);
+    }
+
+    for (port, master, link_port) in all_ports {
+        let index = match result.interfaces.iter().position(|i| i.name == port) {
+            Some(index) => index,
+            None => {
+                let origin = result
+                    .interfaces
+                    .iter()
+                    .find(|i| i.name == master)
+                    .map(|i| i.origin.clone())
+                    .unwrap_or_default();
+                result.interfaces.push(port_interface(&port, &origin));
+                result.interfaces.len() - 1
+            }
+        };
+        let interface = &mut result.interfaces[index];
+        interface.link.master = Some(master);
+        interface.link.port = Some(link_port);
+    }
+
+    for route in routes {
+        add_route(&mut result.interfaces, route, &mut result.findings)?;
+    }
+
+    for (name, ipv6, rule) in rules {
+        let Some(interface) = result.interfaces.iter_mut().find(|i| i.name == name) else {
+            result.findings.warn(
+                Diagnostic::InvalidRoutingRule,
+                Some("rules"),
+                format!("Routing rule for unknown interface {name}"),
+            );
+            continue;
+        };
+        let rules = if ipv6 {
+            &mut interface.ipv6_rule
+        } else {
+            &mut interface.ipv4_rule
+        };
+        rules.get_or_insert_with(Default::default).rules.push(rule);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sysconfig() {
+        let mut vars = parse_sysconfig(
+            r#"
+# comment
+STARTMODE='auto'
+BOOTPROTO="static"
+IPADDR_1=10.0.0.1/24 # trailing comment
+NAME=''
+"#,
+        );
+        assert_eq!(vars.take("STARTMODE"), Some("auto".to_string()));
+        assert_eq!(vars.take("BOOTPROTO"), Some("static".to_string()));
+        assert_eq!(
+            vars.take_prefixed("IPADDR"),
+            vec![("_1".to_string(), "10.0.0.1/24".to_string())]
+        );
+        assert!(vars.take("NAME").is_none());
+    }
+
+    #[test]
+    fn test_parse_routes() {
+        let mut findings = Findings::default();
+        let routes = parse_routes(
+            "default 192.168.101.1 - -\n10.0.0.0 192.168.101.1 255.0.0.0 eth0 metric 5\n2001:db8:1::/64 2001:db8::1 - - mtu 1400 table 100 realm 5\n",
+            Some("eth9"),
+            "ifroute-eth9",
+            &mut findings,
+        );
+        assert_eq!(
+            routes,
+            vec![
+                RouteEntry {
+                    destination: None,
+                    gateway: Some("192.168.101.1".to_string()),
+                    interface: Some("eth9".to_string()),
+                    ..Default::default()
+                },
+                RouteEntry {
+                    destination: Some("10.0.0.0/8".to_string()),
+                    gateway: Some("192.168.101.1".to_string()),
+                    interface: Some("eth0".to_string()),
+                    metric: Some(5),
+                    ..Default::default()
+                },
+                RouteEntry {
+                    destination: Some("2001:db8:1::/64".to_string()),
+                    gateway: Some("2001:db8::1".to_string()),
+                    interface: Some("eth9".to_string()),
+                    kern: RouteKern {
+                        table: Some("100".to_string()),
+                        ..Default::default()
+                    },
+                    metrics: RouteMetrics {
+                        mtu: Some(1400),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            ]
+        );
+        // realm isn't supported
+        assert!(findings.has_warnings());
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let _ = crate::MIGRATION_SETTINGS.set(crate::MigrationSettings::default());
+        let mut findings = Findings::default();
+        let rules = parse_rules(
+            "ipv4 priority 100 from 192.168.1.0/24 table 100\npriority 200 not to 2001:db8::/32 fwmark 0x1/0xff prohibit\n",
+            "ifrule-eth0",
+            &mut findings,
+        );
+        assert!(!findings.has_warnings());
+        assert_eq!(rules.len(), 2);
+        assert!(!rules[0].0);
+        assert_eq!(
+            rules[0].1.to_nm_rule().unwrap(),
+            "priority 100 from 192.168.1.0/24 table 100"
+        );
+        assert!(rules[1].0);
+        assert_eq!(
+            rules[1].1.to_nm_rule().unwrap(),
+            "not priority 200 to 2001:db8::/32 fwmark 0x1/0xff type prohibit"
+        );
+    }
+
+    #[test]
+    fn test_ifcfg_to_interface() {
+        let mut ifcfg = Ifcfg {
+            name: "bond0".to_string(),
+            origin: "compat:suse:/etc/sysconfig/network/ifcfg-bond0".to_string(),
+            vars: parse_sysconfig(
+                r#"
+STARTMODE=auto
+BOOTPROTO=static
+IPADDR=192.168.1.5
+NETMASK=255.255.255.0
+IPADDR_2=2001:db8::5/64
+BONDING_MASTER=yes
+BONDING_SLAVE_1=en0
+BONDING_SLAVE_2=en1
+BONDING_MODULE_OPTS='mode=active-backup miimon=100 primary=en0'
+LLADDR='02:00:33:44:55:11'
+ZONE=public
+"#,
+            ),
+        };
+        let mut findings = Findings::default();
+        let (interface, ports) = ifcfg.to_interface(&mut findings).unwrap();
+        assert!(!findings.has_warnings());
+
+        assert_eq!(interface.control.mode, ControlMode::Boot);
+        assert_eq!(interface.firewall.zone, Some("public".to_string()));
+        let bond = interface.bond.unwrap();
+        assert_eq!(bond.mode, WickedBondMode::ActiveBackup);
+        assert_eq!(bond.miimon.unwrap().frequency, 100);
+        assert_eq!(bond.primary, Some("en0".to_string()));
+        assert_eq!(bond.address, Some("02:00:33:44:55:11".to_string()));
+
+        let ipv4_static = interface.ipv4_static.unwrap();
+        assert_eq!(
+            ipv4_static.addresses.unwrap()[0].local,
+            "192.168.1.5/24".to_string()
+        );
+        assert_eq!(
+            interface.ipv6_static.unwrap().addresses.unwrap()[0].local,
+            "2001:db8::5/64".to_string()
+        );
+        assert_eq!(
+            ports.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(),
+            vec!["en0", "en1"]
+        );
+    }
+
+    #[test]
+    fn test_add_route() {
+        let mut interfaces = vec![Interface {
+            name: "eth0".to_string(),
+            ipv4_static: Some(Ipv4Static {
+                addresses: Some(vec![Address {
+                    local: "192.168.1.5/24".to_string(),
+                    broadcast: None,
+                }]),
+                routes: None,
+            }),
+            ..Default::default()
+        }];
+        let mut findings = Findings::default();
+        let route = RouteEntry {
+            destination: None,
+            gateway: Some("192.168.1.1".to_string()),
+            interface: None,
+            metric: Some(10),
+            ..Default::default()
+        };
+        add_route(&mut interfaces, route, &mut findings).unwrap();
+        let route = RouteEntry {
+            destination: None,
+            gateway: Some("10.0.0.1".to_string()),
+            interface: None,
+            ..Default::default()
+        };
+        add_route(&mut interfaces, route, &mut findings).unwrap();
+
+        let routes = interfaces[0]
+            .ipv4_static
+            .as_ref()
+            .unwrap()
+            .routes
+            .as_ref()
+            .unwrap();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].priority, Some(10));
+        // No interface for 10.0.0.1
+        assert!(findings.has_warnings());
+    }
+
+    #[test]
+    fn test_ifcfg_dhcp_options() {
+        let mut ifcfg = Ifcfg {
+            name: "eth0".to_string(),
+            origin: "compat:suse:/etc/sysconfig/network/ifcfg-eth0".to_string(),
+            vars: parse_sysconfig(
+                r#"
+BOOTPROTO=dhcp
+DHCLIENT_FQDN_ENABLED=yes
+DHCLIENT_FQDN_UPDATE=ptr
+DHCLIENT_VENDOR_CLASS_ID='vendor'
+DHCLIENT6_LEASE_TIME=3600
+DHCLIENT6_MODE=prefix
+DHCLIENT6_PREFIX_HINT='::/56'
+"#,
+            ),
+        };
+        let mut findings = Findings::default();
+        let (interface, _) = ifcfg.to_interface(&mut findings).unwrap();
+        assert!(!findings.has_warnings());
+
+        let ipv4_dhcp = interface.ipv4_dhcp.unwrap();
+        assert_eq!(
+            ipv4_dhcp.fqdn,
+            Some(DhcpFqdn {
+                enabled: FqdnEnabled::Enable,
+                update: FqdnUpdate::Ptr,
+                ..Default::default()
+            })
+        );
+        assert_eq!(ipv4_dhcp.vendor_class, Some("vendor".to_string()));
+        let ipv6_dhcp = interface.ipv6_dhcp.unwrap();
+        assert_eq!(ipv6_dhcp.fqdn, None);
+        assert_eq!(ipv6_dhcp.lease_time, Some(3600));
+        assert_eq!(ipv6_dhcp.mode, "prefix");
+        assert_eq!(ipv6_dhcp.prefix_hint, Some("::/56".to_string()));
+    }
+
+    #[test]
+    fn test_ifcfg_unhandled_variable() {
+        let mut ifcfg = Ifcfg {
+            name: "eth0".to_string(),
+            origin: "compat:suse:/etc/sysconfig/network/ifcfg-eth0".to_string(),
+            vars: parse_sysconfig(
+                "BOOTPROTO=dhcp\nUSERCONTROL=no\nETHTOOL_OPTIONS='-K eth0 tso off'\n",
+            ),
+        };
+        let mut findings = Findings::default();
+        let (interface, _) = ifcfg.to_interface(&mut findings).unwrap();
+        assert!(interface.ipv4_dhcp.is_some());
+        assert!(interface.ipv6_dhcp.is_some());
+        assert!(interface.ethernet.is_some());
+        assert_eq!(
+            findings
+                .iter()
+                .filter_map(|f| f.field.as_deref())
+                .collect::<Vec<_>>(),
+            vec!["ETHTOOL_OPTIONS"]
+        );
+    }
+}
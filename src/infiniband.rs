@@ -116,13 +116,13 @@ mod tests {
         assert!(connections.is_ok());
 
         // Check multicast warning is generated
-        assert!(connections.as_ref().unwrap().has_warnings);
+        assert!(connections.as_ref().unwrap().findings.has_warnings());
 
         testing_logger::validate(|captured_logs| {
             assert_eq!(captured_logs.len(), 1);
             assert_eq!(
                 captured_logs[0].body,
-                "Infiniband multicast in ib0 isn't supported by NetworkManager"
+                "Infiniband multicast in ib0 isn't supported by NetworkManager [W2NM-INFINIBAND-MULTICAST]"
             );
         });
 
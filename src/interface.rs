@@ -1,8 +1,14 @@
 use crate::bond::Bond;
 use crate::bridge::Bridge;
+use crate::client_id::{ClientId, ARPHRD_ETHER, ARPHRD_INFINIBAND};
+use crate::diagnostic::Diagnostic;
+use crate::ethernet::Ethernet;
+use crate::extra::{ExtraSettings, RouteAttributes};
 use crate::infiniband::{Infiniband, InfinibandChild};
+use crate::keyfile::connection_type;
 use crate::netconfig_dhcp::{HostnameOption, NetconfigDhcp};
 use crate::ovs::OvsBridge;
+use crate::report::Findings;
 use crate::team::Team;
 use crate::tuntap::Tap;
 use crate::tuntap::Tun;
@@ -14,12 +20,18 @@ use agama_network::types::{
     Dhcp4Settings, Dhcp6Settings, DhcpClientId, DhcpDuid, DhcpIaid, IpConfig, IpRoute, Ipv4Method,
     Ipv6Method, LinkLocal, MacAddress, Status,
 };
+use anyhow::Context;
 use cidr::IpInet;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, skip_serializing_none, DeserializeFromStr, SerializeDisplay};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::{net::IpAddr, str::FromStr};
 use strum_macros::{Display, EnumString};
+use uuid::Uuid;
+
+/// Namespace of the connection uuids derived with `--stable-uuids`
+const CONNECTION_UUID_NAMESPACE: Uuid = Uuid::from_u128(0x3c5e_9f1a_7d42_5b8e_a0c6_2f91_d4e7_b835);
 
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
@@ -35,6 +47,8 @@ pub struct Interface {
     pub ipv4_dhcp: Option<Ipv4Dhcp>,
     #[serde(rename = "ipv4-auto")]
     pub ipv4_auto: Option<Ipv4Auto>,
+    #[serde(rename = "ipv4-rule")]
+    pub ipv4_rule: Option<RoutingRules>,
     pub ipv6: Ipv6,
     #[serde(rename = "ipv6-static")]
     pub ipv6_static: Option<Ipv6Static>,
@@ -42,6 +56,8 @@ pub struct Interface {
     pub ipv6_dhcp: Option<Ipv6Dhcp>,
     #[serde(rename = "ipv6-auto")]
     pub ipv6_auto: Option<Ipv6Auto>,
+    #[serde(rename = "ipv6-rule")]
+    pub ipv6_rule: Option<RoutingRules>,
     pub dummy: Option<Dummy>,
     pub ethernet: Option<Ethernet>,
     pub bond: Option<Bond>,
@@ -105,6 +121,16 @@ pub enum LinkPortType {
     Team,
 }
 
+/// First NetworkManager version with the `ipv4.forwarding` property.
+const NM_IPV4_FORWARDING_VERSION: (u32, u32) = (1, 54);
+/// First NetworkManager version with the `ipv6.dhcp-pd-hint` property.
+const NM_DHCP_PD_HINT_VERSION: (u32, u32) = (1, 44);
+/// First NetworkManager version with the `ipv4.dhcp-user-class` and `ipv6.dhcp-user-class`
+/// properties.
+const NM_DHCP_USER_CLASS_VERSION: (u32, u32) = (1, 38);
+/// First NetworkManager version with the `weight` route attribute for ipv4 multipath routes.
+const NM_ROUTE_WEIGHT_VERSION: (u32, u32) = (1, 42);
+
 fn default_true() -> bool {
     true
 }
@@ -113,16 +139,17 @@ fn default_true() -> bool {
 pub struct Ipv4 {
     #[serde(default = "default_true")]
     pub enabled: bool,
-    // ignored
-    #[serde(rename = "arp-verify", default = "default_true")]
-    pub arp_verify: bool,
+    pub forwarding: Option<bool>,
+    #[serde(rename = "arp-verify")]
+    pub arp_verify: Option<bool>,
 }
 
 impl Default for Ipv4 {
     fn default() -> Self {
         Self {
             enabled: true,
-            arp_verify: true,
+            forwarding: None,
+            arp_verify: None,
         }
     }
 }
@@ -131,10 +158,53 @@ impl Default for Ipv4 {
 pub struct Ipv6 {
     #[serde(default = "default_true")]
     pub enabled: bool,
+    pub forwarding: Option<bool>,
     pub privacy: Option<Ip6Privacy>,
-    // ignored
-    #[serde(rename = "accept-redirects", default)]
-    pub accept_redirects: bool,
+    #[serde(rename = "accept-ra")]
+    pub accept_ra: Option<AcceptRa>,
+    #[serde(rename = "accept-redirects")]
+    pub accept_redirects: Option<bool>,
+    #[serde(rename = "accept-dad")]
+    pub accept_dad: Option<AcceptDad>,
+    #[serde(rename = "addr-gen-mode")]
+    pub addr_gen_mode: Option<AddrGenMode>,
+    #[serde(rename = "stable-secret")]
+    pub stable_secret: Option<String>,
+}
+
+/// Kernel `accept_ra` mode, `router` also accepts router advertisements while forwarding.
+#[derive(Debug, PartialEq, SerializeDisplay, DeserializeFromStr, EnumString, Clone, Display)]
+pub enum AcceptRa {
+    #[strum(to_string = "disable", serialize = "0")]
+    Disable,
+    #[strum(to_string = "host", serialize = "1")]
+    Host,
+    #[strum(to_string = "router", serialize = "2")]
+    Router,
+}
+
+/// Kernel `accept_dad` mode, `fail-protocol` also disables ipv6 on a duplicate link-local address.
+#[derive(Debug, PartialEq, SerializeDisplay, DeserializeFromStr, EnumString, Clone, Display)]
+pub enum AcceptDad {
+    #[strum(to_string = "disable", serialize = "0")]
+    Disable = 0,
+    #[strum(to_string = "fail-address", serialize = "1")]
+    FailAddress = 1,
+    #[strum(to_string = "fail-protocol", serialize = "2")]
+    FailProtocol = 2,
+}
+
+/// Kernel `addr_gen_mode` of the ipv6 link-local and SLAAC addresses.
+#[derive(Debug, PartialEq, SerializeDisplay, DeserializeFromStr, EnumString, Clone, Display)]
+pub enum AddrGenMode {
+    #[strum(to_string = "eui64", serialize = "0")]
+    Eui64,
+    #[strum(to_string = "none", serialize = "1")]
+    None,
+    #[strum(to_string = "stable-privacy", serialize = "2")]
+    StablePrivacy,
+    #[strum(to_string = "random", serialize = "3")]
+    Random,
 }
 
 #[derive(
@@ -152,8 +222,13 @@ impl Default for Ipv6 {
     fn default() -> Self {
         Self {
             enabled: true,
+            forwarding: None,
             privacy: None,
-            accept_redirects: false,
+            accept_ra: None,
+            accept_redirects: None,
+            accept_dad: None,
+            addr_gen_mode: None,
+            stable_secret: None,
         }
     }
 }
@@ -170,10 +245,8 @@ pub struct Ipv4Static {
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Ipv4Dhcp {
     pub enabled: bool,
-    // ignored
     #[serde(default = "default_flags")]
     pub flags: String,
-    // ignored
     #[serde(default = "default_v4_update")]
     pub update: String,
     pub hostname: Option<String>,
@@ -181,14 +254,19 @@ pub struct Ipv4Dhcp {
     pub create_cid: CreateCid,
     #[serde(rename = "client-id")]
     pub client_id: Option<String>,
-    // ignored
     #[serde(rename = "defer-timeout", default = "default_defer_timeout")]
     pub defer_timeout: u32,
-    // ignored
     #[serde(rename = "recover-lease", default = "default_true")]
     pub recover_lease: bool,
     #[serde(rename = "release-lease", default)]
     pub release_lease: bool,
+    pub fqdn: Option<DhcpFqdn>,
+    #[serde(rename = "vendor-class")]
+    pub vendor_class: Option<String>,
+    #[serde(rename = "user-class")]
+    pub user_class: Option<UserClass>,
+    #[serde(rename = "lease-time")]
+    pub lease_time: Option<u32>,
 }
 
 #[derive(
@@ -202,6 +280,69 @@ pub enum CreateCid {
     Disable,
 }
 
+/// Settings of the DHCP FQDN option (RFC 4702 and RFC 4704).
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DhcpFqdn {
+    pub enabled: FqdnEnabled,
+    pub update: FqdnUpdate,
+    pub encode: bool,
+    pub qualify: bool,
+}
+
+impl Default for DhcpFqdn {
+    fn default() -> Self {
+        Self {
+            enabled: FqdnEnabled::default(),
+            update: FqdnUpdate::default(),
+            encode: true,
+            qualify: true,
+        }
+    }
+}
+
+/// Whether the FQDN option is sent, by default only for fully qualified hostnames.
+#[derive(
+    Debug, PartialEq, Default, SerializeDisplay, DeserializeFromStr, EnumString, Display, Clone,
+)]
+pub enum FqdnEnabled {
+    #[default]
+    #[strum(to_string = "default", serialize = "")]
+    Default,
+    #[strum(to_string = "enable", serialize = "true", serialize = "yes")]
+    Enable,
+    #[strum(to_string = "disable", serialize = "false", serialize = "no")]
+    Disable,
+}
+
+/// Which DNS records the DHCP server updates, `ptr` leaves the A/AAAA record to the client.
+#[derive(
+    Debug, PartialEq, Default, SerializeDisplay, DeserializeFromStr, EnumString, Display, Clone,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum FqdnUpdate {
+    #[default]
+    Both,
+    Ptr,
+    None,
+}
+
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserClass {
+    pub format: Option<String>,
+    #[serde(rename = "identifier")]
+    pub identifiers: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VendorClass6 {
+    #[serde(rename = "enterprise-number")]
+    pub enterprise_number: Option<u32>,
+    pub data: Vec<String>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Ipv4Auto {
     pub enabled: bool,
@@ -232,6 +373,10 @@ impl Default for Ipv4Dhcp {
             defer_timeout: default_defer_timeout(),
             recover_lease: true,
             release_lease: false,
+            fqdn: None,
+            vendor_class: None,
+            user_class: None,
+            lease_time: None,
         }
     }
 }
@@ -258,20 +403,18 @@ pub struct Address {
 pub struct Ipv6Dhcp {
     pub enabled: bool,
     pub mode: String,
-    // ignored
     #[serde(default = "default_flags")]
     pub flags: String,
-    // ignored
     #[serde(default = "default_v6_dhcp_update")]
     pub update: String,
-    // ignored
     #[serde(rename = "rapid-commit", default = "default_true")]
     pub rapid_commit: bool,
+    /// Prefix (length) to request with the prefix delegation, e.g. `::/56`
+    #[serde(rename = "prefix-hint")]
+    pub prefix_hint: Option<String>,
     pub hostname: Option<String>,
-    // ignored
     #[serde(rename = "defer-timeout", default = "default_defer_timeout")]
     pub defer_timeout: u32,
-    // ignored
     #[serde(rename = "recover-lease", default = "default_true")]
     pub recover_lease: bool,
     // ignored
@@ -279,6 +422,29 @@ pub struct Ipv6Dhcp {
     pub refresh_lease: bool,
     #[serde(rename = "release-lease", default)]
     pub release_lease: bool,
+    pub fqdn: Option<DhcpFqdn>,
+    #[serde(rename = "vendor-class")]
+    pub vendor_class: Option<VendorClass6>,
+    #[serde(rename = "user-class")]
+    pub user_class: Option<UserClass>,
+    #[serde(rename = "lease-time")]
+    pub lease_time: Option<u32>,
+}
+
+/// DHCPv6 modes of wicked, they can be combined (e.g. `managed,prefix`).
+const IPV6_DHCP_MODES: [&str; 4] = ["auto", "info", "managed", "prefix"];
+
+impl Ipv6Dhcp {
+    fn modes(&self) -> impl Iterator<Item = &str> {
+        self.mode
+            .split([',', ' '])
+            .map(|m| m.trim())
+            .filter(|m| !m.is_empty())
+    }
+
+    fn has_mode(&self, mode: &str) -> bool {
+        self.modes().any(|m| m == mode)
+    }
 }
 
 fn default_v6_dhcp_update() -> String {
@@ -293,11 +459,16 @@ impl Default for Ipv6Dhcp {
             flags: default_flags(),
             update: default_v6_dhcp_update(),
             rapid_commit: true,
+            prefix_hint: None,
             hostname: None,
             defer_timeout: default_defer_timeout(),
             recover_lease: true,
             refresh_lease: false,
             release_lease: false,
+            fqdn: None,
+            vendor_class: None,
+            user_class: None,
+            lease_time: None,
         }
     }
 }
@@ -306,7 +477,6 @@ impl Default for Ipv6Dhcp {
 #[serde(default)]
 pub struct Ipv6Auto {
     pub enabled: bool,
-    // ignored
     #[serde(default = "default_v6_dhcp_update")]
     pub update: String,
 }
@@ -336,17 +506,78 @@ pub struct Route {
     #[serde(rename = "nexthop")]
     pub nexthops: Option<Vec<Nexthop>>,
     pub priority: Option<u32>,
+    #[serde(rename = "pref-source")]
+    pub pref_source: Option<String>,
+    pub kern: Option<RouteKern>,
+    pub metrics: Option<RouteMetrics>,
 }
 
 #[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+pub struct RouteKern {
+    pub table: Option<String>,
+    #[serde(rename = "type")]
+    pub route_type: Option<String>,
+    pub scope: Option<String>,
+    pub protocol: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+pub struct RouteMetrics {
+    pub mtu: Option<u32>,
+    pub window: Option<u32>,
+    pub initcwnd: Option<u32>,
+}
+
 #[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
-pub struct Ethernet {
-    pub address: Option<String>,
+#[serde(default)]
+pub struct RoutingRules {
+    #[serde(rename = "rule")]
+    pub rules: Vec<Rule>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// Policy routing rule, the equivalent of `ip rule`.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    pub priority: Option<u32>,
+    #[serde(rename = "match")]
+    pub selector: RuleMatch,
+    pub action: RuleAction,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleMatch {
+    pub invert: bool,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub iif: Option<String>,
+    pub oif: Option<String>,
+    pub fwmark: Option<String>,
+    pub fwmask: Option<String>,
+    pub tos: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleAction {
+    #[serde(rename = "type")]
+    pub action_type: Option<String>,
+    pub table: Option<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct Nexthop {
     pub gateway: String,
+    pub device: Option<String>,
+    pub weight: Option<u32>,
+    pub flags: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Default, Serialize, Deserialize)]
@@ -386,13 +617,16 @@ impl From<ControlMode> for bool {
 #[derive(Default)]
 pub struct ConnectionResult {
     pub connections: Vec<model::Connection>,
-    pub has_warnings: bool,
+    /// Settings not covered by the agama model, by connection id
+    pub extra_settings: HashMap<String, ExtraSettings>,
+    pub findings: Findings,
 }
 
 #[derive(Default)]
 pub struct IpConfigResult {
     ip_config: IpConfig,
-    has_warnings: bool,
+    extra: ExtraSettings,
+    findings: Findings,
 }
 
 impl From<&LinkPort> for model::PortConfig {
@@ -426,9 +660,12 @@ impl Interface {
         let mut connection_result = ConnectionResult::default();
 
         let ip_config = self.to_ip_config(netconfig_dhcp)?;
-        connection_result.has_warnings |= ip_config.has_warnings;
+        connection_result.findings.append(ip_config.findings);
+        let mut extra = ip_config.extra;
 
-        connection_result.has_warnings |= has_unhandled_field(self);
+        connection_result
+            .findings
+            .append(check_unhandled_fields(self));
 
         let mut connection = model::Connection {
             id: self.name.clone(),
@@ -456,33 +693,43 @@ impl Interface {
             } else if let LinkPortType::Team = port.port_type {
                 // Warn about team port options that can't be translated
                 if let Some(queue_id) = port.queue_id {
-                    if let Some(master) = self.link.master.as_ref() {
-                        log::warn!(
+                    let message = if let Some(master) = self.link.master.as_ref() {
+                        format!(
                             "Team port '{}' queue_id={} is not supported in NetworkManager bond configuration. \
                              To set manually after bond creation: echo \"{}:{}\" > /sys/class/net/{}/bonding/queue_id",
                             self.name, queue_id, self.name, queue_id, master
-                        );
+                        )
                     } else {
-                        log::warn!(
+                        format!(
                             "Team port '{}' queue_id={} is not supported in NetworkManager bond configuration.",
                             self.name, queue_id
-                        );
-                    }
-                    connection_result.has_warnings = true;
+                        )
+                    };
+                    connection_result.findings.warn(
+                        Diagnostic::TeamQueueId,
+                        Some("link.port.queue_id"),
+                        message,
+                    );
                 }
                 if port.lacp_key.is_some() {
-                    log::warn!(
-                        "Team port '{}' lacp_key option is not supported in bond configuration",
-                        self.name
+                    connection_result.findings.warn(
+                        Diagnostic::TeamLacpKey,
+                        Some("link.port.lacp_key"),
+                        format!(
+                            "Team port '{}' lacp_key option is not supported in bond configuration",
+                            self.name
+                        ),
                     );
-                    connection_result.has_warnings = true;
                 }
                 if port.lacp_prio.is_some() {
-                    log::warn!(
-                        "Team port '{}' lacp_prio option is not supported in kernel 6.12 - requires kernel 6.18+ (actor_port_prio)",
-                        self.name
+                    connection_result.findings.warn(
+                        Diagnostic::TeamLacpPrio,
+                        Some("link.port.lacp_prio"),
+                        format!(
+                            "Team port '{}' lacp_prio option is not supported in kernel 6.12 - requires kernel 6.18+ (actor_port_prio)",
+                            self.name
+                        ),
                     );
-                    connection_result.has_warnings = true;
                 }
             }
         }
@@ -497,6 +744,7 @@ impl Interface {
 
         if let Some(ethernet) = &self.ethernet {
             connection.custom_mac_address = MacAddress::try_from(&ethernet.address)?;
+            ethernet.add_link_settings(&self.name, &mut extra, &mut connection_result.findings);
             connection.config = model::ConnectionConfig::Ethernet;
             connection_result.connections.push(connection);
         } else if let Some(dummy) = &self.dummy {
@@ -508,14 +756,18 @@ impl Interface {
             connection.config = bond.into();
             connection_result.connections.push(connection);
         } else if let Some(team) = &self.team {
-            log::info!(
-                "Converting team interface '{}' to bond - team is no longer supported",
-                self.name
+            connection_result.findings.info(
+                Diagnostic::TeamConverted,
+                Some("team"),
+                format!(
+                    "Converting team interface '{}' to bond - team is no longer supported",
+                    self.name
+                ),
             );
             connection.custom_mac_address = MacAddress::try_from(&team.address)?;
-            let (config, has_warnings) = team.to_connection_config();
+            let (config, findings) = team.to_connection_config();
             connection.config = config;
-            connection_result.has_warnings |= has_warnings;
+            connection_result.findings.append(findings);
             connection_result.connections.push(connection);
         } else if let Some(vlan) = &self.vlan {
             connection.custom_mac_address = MacAddress::try_from(&vlan.address)?;
@@ -528,14 +780,20 @@ impl Interface {
         } else if let Some(wireless) = &self.wireless {
             if let Some(networks) = &wireless.networks {
                 if networks.len() > 1 {
-                    log::info!("{} has multiple networks defined, these will be split into different connections in NM", connection.id);
+                    connection_result.findings.info(
+                        Diagnostic::WirelessMultipleNetworks,
+                        Some("wireless.networks"),
+                        format!("{} has multiple networks defined, these will be split into different connections in NM", connection.id),
+                    );
                 }
                 for (i, network) in networks.iter().enumerate() {
                     let mut wireless_connection = connection.clone();
                     if networks.len() > 1 {
                         wireless_connection.id.push_str(&format!("-{i}"));
                     }
-                    wireless_connection.config = network.try_into()?;
+                    let (config, findings) = network.to_connection_config()?;
+                    wireless_connection.config = config;
+                    connection_result.findings.append(findings);
                     if let Some(wpa_eap) = &network.wpa_eap {
                         wireless_connection.ieee_8021x_config = Some(wpa_eap.try_into()?);
                     }
@@ -544,21 +802,27 @@ impl Interface {
             }
         } else if let Some(infiniband) = &self.infiniband {
             if infiniband.multicast.is_some() {
-                log::warn!(
-                    "Infiniband multicast in {} isn't supported by NetworkManager",
-                    self.name
+                connection_result.findings.warn(
+                    Diagnostic::InfinibandMulticast,
+                    Some("infiniband.multicast"),
+                    format!(
+                        "Infiniband multicast in {} isn't supported by NetworkManager",
+                        self.name
+                    ),
                 );
-                connection_result.has_warnings = true;
             }
             connection.config = infiniband.into();
             connection_result.connections.push(connection);
         } else if let Some(infiniband_child) = &self.infiniband_child {
             if infiniband_child.multicast.is_some() {
-                log::warn!(
-                    "Infiniband multicast in {} isn't supported by NetworkManager",
-                    self.name
+                connection_result.findings.warn(
+                    Diagnostic::InfinibandMulticast,
+                    Some("infiniband.multicast"),
+                    format!(
+                        "Infiniband multicast in {} isn't supported by NetworkManager",
+                        self.name
+                    ),
                 );
-                connection_result.has_warnings = true;
             }
             connection.config = infiniband_child.into();
             connection_result.connections.push(connection);
@@ -606,6 +870,21 @@ impl Interface {
             connection_result.connections.push(connection);
         }
 
+        if !extra.is_empty() {
+            for connection in &connection_result.connections {
+                if connection.interface.as_ref() == Some(&self.name) {
+                    connection_result
+                        .extra_settings
+                        .insert(connection.id.clone(), extra.clone());
+                }
+            }
+        }
+
+        if settings.stable_uuids {
+            stable_uuids(&mut connection_result.connections);
+        }
+
+        connection_result.findings.set_interface(&self.name);
         Ok(connection_result)
     }
 
@@ -625,18 +904,21 @@ impl Interface {
         } else {
             Ipv4Method::Disabled
         };
-        let method6 =
-            if self.ipv6_dhcp.is_some() && self.ipv6_dhcp.as_ref().unwrap().mode == "managed" {
-                Ipv6Method::Dhcp
-            } else if self.ipv6_dhcp.is_some() {
-                Ipv6Method::Auto
-            } else if self.ipv6_static.is_some() {
-                Ipv6Method::Manual
-            } else if !self.ipv6.enabled {
-                Ipv6Method::Disabled
-            } else {
-                Ipv6Method::Auto
-            };
+        let mut method6 = if self
+            .ipv6_dhcp
+            .as_ref()
+            .is_some_and(|dhcp| dhcp.has_mode("managed"))
+        {
+            Ipv6Method::Dhcp
+        } else if self.ipv6_dhcp.is_some() {
+            Ipv6Method::Auto
+        } else if self.ipv6_static.is_some() {
+            Ipv6Method::Manual
+        } else if !self.ipv6.enabled {
+            Ipv6Method::Disabled
+        } else {
+            Ipv6Method::Auto
+        };
 
         let link_local4 = if let Some(auto4) = &self.ipv4_auto {
             if auto4.enabled {
@@ -688,11 +970,14 @@ impl Interface {
                             }
                         };
                         if broadcast_addr != local_addr.last_address() {
-                            log::warn!(
-                                "Broadcast \"{}\" for {}: Custom broadcast addresses are not supported by NetworkManager",
-                                broadcast, self.name
+                            ipconfig_result.findings.warn(
+                                Diagnostic::CustomBroadcast,
+                                Some("ipv4_static.address.broadcast"),
+                                format!(
+                                    "Broadcast \"{}\" for {}: Custom broadcast addresses are not supported by NetworkManager",
+                                    broadcast, self.name
+                                ),
                             );
-                            ipconfig_result.has_warnings = true;
                         }
                     }
 
@@ -700,16 +985,13 @@ impl Interface {
                 }
             }
             if let Some(routes) = &ipv4_static.routes {
-                for route in routes {
-                    routes4.push(match route.try_into() {
-                        Ok(route) => route,
-                        Err(e) => {
-                            log::warn!("Failed parsing ipv4 route for {}: {}", self.name, e);
-                            ipconfig_result.has_warnings = true;
-                            continue;
-                        }
-                    });
-                }
+                self.add_routes(
+                    "ipv4",
+                    routes,
+                    &mut routes4,
+                    &mut ipconfig_result.extra.route_attributes4,
+                    &mut ipconfig_result.findings,
+                );
             }
         }
         if let Some(ipv6_static) = &self.ipv6_static {
@@ -729,15 +1011,39 @@ impl Interface {
                 }
             }
             if let Some(routes) = &ipv6_static.routes {
-                for route in routes {
-                    routes6.push(match route.try_into() {
-                        Ok(route) => route,
-                        Err(e) => {
-                            log::warn!("Failed parsing ipv6 route for {}: {}", self.name, e);
-                            ipconfig_result.has_warnings = true;
-                            continue;
-                        }
-                    });
+                self.add_routes(
+                    "ipv6",
+                    routes,
+                    &mut routes6,
+                    &mut ipconfig_result.extra.route_attributes6,
+                    &mut ipconfig_result.findings,
+                );
+            }
+        }
+
+        for (family, rules, nm_rules) in [
+            (
+                "ipv4",
+                &self.ipv4_rule,
+                &mut ipconfig_result.extra.routing_rules4,
+            ),
+            (
+                "ipv6",
+                &self.ipv6_rule,
+                &mut ipconfig_result.extra.routing_rules6,
+            ),
+        ] {
+            for rule in rules.iter().flat_map(|r| &r.rules) {
+                match rule.to_nm_rule() {
+                    Ok(nm_rule) => nm_rules.push(nm_rule),
+                    Err(e) => ipconfig_result.findings.warn(
+                        Diagnostic::InvalidRoutingRule,
+                        Some(format!("{family}_rule").as_str()),
+                        format!(
+                            "Failed parsing {family} routing rule for {}: {e}",
+                            self.name
+                        ),
+                    ),
                 }
             }
         }
@@ -746,6 +1052,7 @@ impl Interface {
         let mut dhcp6_settings: Dhcp6Settings = Dhcp6Settings::default();
 
         let mut never_default4 = Some(false);
+        let mut never_default6 = Some(false);
         if let Some(ipv4_dhcp) = &self.ipv4_dhcp {
             if let Some(hostname) = &ipv4_dhcp.hostname {
                 dhcp4_settings.send_hostname = Some(true);
@@ -759,19 +1066,34 @@ impl Interface {
             } else {
                 dhcp4_settings.send_hostname = Some(false);
             }
+            if let Some(fqdn) = &ipv4_dhcp.fqdn {
+                self.add_dhcp_fqdn(
+                    "ipv4",
+                    fqdn,
+                    &mut dhcp4_settings.hostname,
+                    &mut dhcp4_settings.send_hostname,
+                    &mut ipconfig_result,
+                );
+            }
+            if let Some(vendor_class) = &ipv4_dhcp.vendor_class {
+                ipconfig_result
+                    .extra
+                    .set("ipv4", "dhcp-vendor-class-identifier", vendor_class);
+            }
             dhcp4_settings.send_release = Some(ipv4_dhcp.release_lease);
-            dhcp4_settings.client_id = if let Some(client_id) = &ipv4_dhcp.client_id {
-                DhcpClientId::Id(client_id.clone())
-            } else {
-                match ipv4_dhcp.create_cid {
+            dhcp4_settings.client_id = match self.nm_client_id(ipv4_dhcp, &mut ipconfig_result) {
+                Some(client_id) => DhcpClientId::Id(client_id),
+                None => match ipv4_dhcp.create_cid {
                     CreateCid::Rfc4361 => DhcpClientId::Ipv6Duid,
                     CreateCid::Rfc2132 => DhcpClientId::Mac,
                     CreateCid::Disable => DhcpClientId::None,
-                }
+                },
             };
             dhcp4_settings.iaid = DhcpIaid::Mac;
             dhcp6_settings.duid = DhcpDuid::Llt;
-            never_default4 = Some(!ipv4_dhcp.update.split(",").any(|x| x == "default-route"));
+            never_default4 = Some(
+                dropped_updates(&ipv4_dhcp.update, &default_v4_update()).contains("default-route"),
+            );
         }
         let dhcp4_settings: Option<Dhcp4Settings> = Some(dhcp4_settings);
 
@@ -788,12 +1110,71 @@ impl Interface {
             } else {
                 dhcp6_settings.send_hostname = Some(false);
             }
+            if let Some(fqdn) = &ipv6_dhcp.fqdn {
+                self.add_dhcp_fqdn(
+                    "ipv6",
+                    fqdn,
+                    &mut dhcp6_settings.hostname,
+                    &mut dhcp6_settings.send_hostname,
+                    &mut ipconfig_result,
+                );
+            }
             dhcp6_settings.send_release = Some(ipv6_dhcp.release_lease);
             dhcp6_settings.iaid = DhcpIaid::Mac;
             dhcp6_settings.duid = DhcpDuid::Llt;
+            never_default6 = Some(
+                dropped_updates(&ipv6_dhcp.update, &default_v6_dhcp_update())
+                    .contains("default-route"),
+            );
         }
         let dhcp6_settings: Option<Dhcp6Settings> = Some(dhcp6_settings);
 
+        self.add_dhcp_updates(&mut ipconfig_result);
+        if let Some(ipv4_dhcp) = &self.ipv4_dhcp {
+            add_dhcp_timing(
+                "ipv4",
+                &ipv4_dhcp.flags,
+                ipv4_dhcp.defer_timeout,
+                &mut ipconfig_result.extra,
+            );
+        }
+        if let Some(ipv6_dhcp) = &self.ipv6_dhcp {
+            add_dhcp_timing(
+                "ipv6",
+                &ipv6_dhcp.flags,
+                ipv6_dhcp.defer_timeout,
+                &mut ipconfig_result.extra,
+            );
+        }
+        self.add_addr_gen_mode(&mut ipconfig_result);
+        self.add_ra_settings(&mut method6, &mut ipconfig_result);
+        self.add_dad(&mut ipconfig_result);
+        let settings = MIGRATION_SETTINGS.get().unwrap();
+        self.add_forwarding(
+            settings
+                .nm_version
+                .is_some_and(|version| version >= NM_IPV4_FORWARDING_VERSION),
+            &mut ipconfig_result,
+        );
+        self.add_dhcp6_mode(
+            settings
+                .nm_version
+                .is_some_and(|version| version >= NM_DHCP_PD_HINT_VERSION),
+            &mut ipconfig_result,
+        );
+        if settings.ipv6_shared.contains(&self.name) {
+            ipconfig_result.extra.set("ipv6", "method", "shared");
+        }
+        let nm_user_class = settings
+            .nm_version
+            .is_some_and(|version| version >= NM_DHCP_USER_CLASS_VERSION);
+        if let Some(user_class) = self.ipv4_dhcp.as_ref().and_then(|d| d.user_class.as_ref()) {
+            self.add_dhcp_user_class("ipv4", user_class, nm_user_class, &mut ipconfig_result);
+        }
+        if let Some(user_class) = self.ipv6_dhcp.as_ref().and_then(|d| d.user_class.as_ref()) {
+            self.add_dhcp_user_class("ipv6", user_class, nm_user_class, &mut ipconfig_result);
+        }
+
         let mut ip6_privacy: Option<i32> = None;
         if let Some(privacy) = &self.ipv6.privacy {
             ip6_privacy = Some(privacy.clone() as i32);
@@ -810,237 +1191,987 @@ impl Interface {
             ip6_privacy,
             link_local4,
             never_default4,
+            never_default6,
             ..Default::default()
         };
         Ok(ipconfig_result)
     }
-}
 
-impl TryFrom<&Route> for IpRoute {
-    type Error = anyhow::Error;
-    fn try_from(route: &Route) -> Result<Self, Self::Error> {
-        let mut next_hop: Option<IpAddr> = None;
-        if let Some(nexthops) = &route.nexthops {
-            if nexthops.len() > 1 {
-                return Err(anyhow::anyhow!(
-                    "Multipath routing isn't natively supported by NetworkManager"
-                ));
-            } else {
-                next_hop = Some(IpAddr::from_str(&nexthops[0].gateway)?);
+    /// Maps the `dns` and `hostname` entries of the DHCP and router advertisement `update` lists,
+    /// `default-route` is handled with never-default.
+    fn add_dhcp_updates(&self, ipconfig_result: &mut IpConfigResult) {
+        let dropped4 = self
+            .ipv4_dhcp
+            .as_ref()
+            .map(|dhcp| dropped_updates(&dhcp.update, &default_v4_update()));
+        let dropped6 = self
+            .ipv6_dhcp
+            .as_ref()
+            .map(|dhcp| dropped_updates(&dhcp.update, &default_v6_dhcp_update()));
+        let auto6_dns = self
+            .ipv6_auto
+            .as_ref()
+            .map(|auto| auto.update.split(',').any(|u| u.trim() == "dns"));
+
+        if dropped4.as_ref().is_some_and(|d| d.contains("dns")) {
+            ipconfig_result.extra.set("ipv4", "ignore-auto-dns", "true");
+        }
+        // NetworkManager can't ignore the DNS servers of only one of DHCPv6 and RA
+        let dhcp6_dns = dropped6.as_ref().map(|d| !d.contains("dns"));
+        match (dhcp6_dns, auto6_dns) {
+            (Some(false), None | Some(false)) | (None, Some(false)) => {
+                ipconfig_result.extra.set("ipv6", "ignore-auto-dns", "true");
             }
+            (Some(true), Some(false)) | (Some(false), Some(true)) => ipconfig_result.findings.info(
+                Diagnostic::DhcpUpdate,
+                Some("ipv6_auto.update"),
+                format!(
+                    "DNS servers of {} are only accepted from one of DHCPv6 and router advertisements, NetworkManager accepts them from both",
+                    self.name
+                ),
+            ),
+            _ => {}
         }
-        let destination = if let Some(destination) = &route.destination {
-            IpInet::from_str(destination)?
-        } else if let Some(next_hop) = next_hop {
-            // default route
-            let default_ip = if next_hop.is_ipv4() {
-                IpAddr::from_str("0.0.0.0")?
-            } else {
-                IpAddr::from_str("::")?
-            };
-            IpInet::new(default_ip, 0)?
-        } else {
-            return Err(anyhow::anyhow!(
-                "Route contains neither destination nor next-hop"
-            ));
-        };
-        let metric = route.priority;
-        Ok(IpRoute {
-            destination,
-            next_hop,
-            metric,
-        })
-    }
-}
-
-pub fn check_extra_flags(flags_a: &str, flags_b: &str, diff_flags: &str) -> bool {
-    let flags_a: HashSet<&str> = flags_a
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    let flags_b: HashSet<&str> = flags_b
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    let diff_flags: HashSet<&str> = diff_flags
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    let calculated_diff: HashSet<&str> = flags_a.symmetric_difference(&flags_b).copied().collect();
-    calculated_diff == diff_flags
-}
-
-fn check_dhcp_update(update: &str, default_update: &str, interface_name: &str, dhcp_type: &str) {
-    let default_set: HashSet<&str> = default_update
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty() && *s != "default-route")
-        .collect();
-
-    let update_set: HashSet<&str> = update
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty() && *s != "default-route")
-        .collect();
-
-    for missing in default_set.difference(&update_set) {
-        log::info!(
-            "{} in interface {} is missing default entry: {}",
-            dhcp_type,
-            interface_name,
-            missing
-        );
-    }
-
-    for added in update_set.difference(&default_set) {
-        log::info!(
-            "{} in interface {} has extra entry: {}",
-            dhcp_type,
-            interface_name,
-            added
-        );
-    }
-}
-
-fn has_unhandled_field(interface: &Interface) -> bool {
-    let mut warnings = false;
 
-    let ipv4 = &interface.ipv4;
-    let ipv4_default = Ipv4::default();
-    if ipv4.arp_verify != ipv4_default.arp_verify {
-        log::warn!(
-            "Unhandled field in interface {}: {}",
-            interface.name,
-            stringify!(ipv4.arp_verify)
-        );
-        warnings = true;
-    }
+        // NetworkManager prefers a configured MTU over the one from DHCP
+        if dropped4.as_ref().is_some_and(|d| d.contains("mtu")) && self.link.mtu.is_none() {
+            ipconfig_result.findings.warn(
+                Diagnostic::DhcpMtu,
+                Some("ipv4_dhcp.update"),
+                format!(
+                    "NetworkManager always applies the MTU from DHCP on {} unless an MTU is configured",
+                    self.name
+                ),
+            );
+        }
 
-    let ipv6 = &interface.ipv6;
-    let ipv6_default = Ipv6::default();
-    if ipv6.accept_redirects != ipv6_default.accept_redirects {
-        log::warn!(
-            "Unhandled field in interface {}: {}",
-            interface.name,
-            stringify!(ipv6.accept_redirects)
-        );
-        warnings = true;
+        let hostname: Vec<bool> = [&dropped4, &dropped6]
+            .into_iter()
+            .flatten()
+            .map(|d| !d.contains("hostname"))
+            .collect();
+        if !hostname.is_empty() && hostname.iter().all(|accepted| !accepted) {
+            ipconfig_result.extra.set("hostname", "from-dhcp", 0);
+        } else if hostname.iter().any(|accepted| !accepted) {
+            ipconfig_result.findings.info(
+                Diagnostic::DhcpUpdate,
+                Some("ipv4_dhcp.update"),
+                format!(
+                    "The hostname of {} is only accepted from one of DHCPv4 and DHCPv6, NetworkManager accepts it from both",
+                    self.name
+                ),
+            );
+        }
     }
 
-    if let Some(ipv4_dhcp) = &interface.ipv4_dhcp {
-        let ipv4_dhcp_default = Ipv4Dhcp::default();
-        if ipv4_dhcp.flags != ipv4_dhcp_default.flags
-            && !(check_extra_flags(
-                ipv4_dhcp.flags.as_str(),
-                ipv4_dhcp_default.flags.as_str(),
-                "primary",
-            ) && interface.ipv4_auto.is_some())
+    /// Duplicate address detection is enabled by default in NetworkManager (200ms for ipv4) and
+    /// the kernel, so only disabling it or `fail-protocol` needs a setting.
+    fn add_dad(&self, ipconfig_result: &mut IpConfigResult) {
+        if self.ipv4.arp_verify == Some(false) {
+            ipconfig_result.extra.set("ipv4", "dad-timeout", 0);
+        }
+        if let Some(accept_dad @ (AcceptDad::Disable | AcceptDad::FailProtocol)) =
+            &self.ipv6.accept_dad
         {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv4_dhcp.flags)
+            ipconfig_result.extra.set_sysctl(
+                format!("net/ipv6/conf/{}/accept_dad", self.name),
+                accept_dad.clone() as u8,
             );
-            warnings = true;
         }
-        check_dhcp_update(
-            &ipv4_dhcp.update,
-            &ipv4_dhcp_default.update,
-            &interface.name,
-            "ipv4_dhcp.update",
-        );
-        if ipv4_dhcp.defer_timeout != ipv4_dhcp_default.defer_timeout {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv4_dhcp.defer_timeout)
-            );
-            warnings = true;
+    }
+
+    /// NetworkManager only has a property for ipv4 forwarding, everything else is kept with a
+    /// sysctl drop-in.
+    fn add_forwarding(&self, nm_ipv4_forwarding: bool, ipconfig_result: &mut IpConfigResult) {
+        let extra = &mut ipconfig_result.extra;
+        if let Some(forwarding) = self.ipv4.forwarding {
+            if nm_ipv4_forwarding {
+                extra.set("ipv4", "forwarding", u8::from(forwarding));
+            } else {
+                extra.set_sysctl(
+                    format!("net/ipv4/conf/{}/forwarding", self.name),
+                    u8::from(forwarding),
+                );
+            }
         }
-        if ipv4_dhcp.recover_lease != ipv4_dhcp_default.recover_lease {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv4_dhcp.recover_lease)
+        if let Some(forwarding) = self.ipv6.forwarding {
+            extra.set_sysctl(
+                format!("net/ipv6/conf/{}/forwarding", self.name),
+                u8::from(forwarding),
             );
-            warnings = true;
         }
     }
 
-    if let Some(ipv6_dhcp) = &interface.ipv6_dhcp {
-        let ipv6_dhcp_default = Ipv6Dhcp::default();
-        if ipv6_dhcp.flags != ipv6_dhcp_default.flags {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv6_dhcp.flags)
+    /// NetworkManager requests a delegated prefix while a connection with ipv6 method `shared`
+    /// (see `--ipv6-shared`) is active, the hint of wicked's `prefix` mode is kept.
+    fn add_dhcp6_mode(&self, nm_pd_hint: bool, ipconfig_result: &mut IpConfigResult) {
+        let Some(ipv6_dhcp) = &self.ipv6_dhcp else {
+            return;
+        };
+        if ipv6_dhcp.has_mode("info") && !ipv6_dhcp.has_mode("managed") {
+            ipconfig_result.findings.info(
+                Diagnostic::Ipv6DhcpMode,
+                Some("ipv6_dhcp.mode"),
+                format!(
+                    "NetworkManager requests addresses on {} when router advertisements have the managed flag, wicked only requested information",
+                    self.name
+                ),
             );
-            warnings = true;
         }
-
-        check_dhcp_update(
-            &ipv6_dhcp.update,
-            &ipv6_dhcp_default.update,
-            &interface.name,
-            "ipv6_dhcp.update",
-        );
-        if ipv6_dhcp.rapid_commit != ipv6_dhcp_default.rapid_commit {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv6_dhcp.rapid_commit)
-            );
-            warnings = true;
+        if !ipv6_dhcp.has_mode("prefix") {
+            return;
         }
-        if ipv6_dhcp.defer_timeout != ipv6_dhcp_default.defer_timeout {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv6_dhcp.defer_timeout)
-            );
-            warnings = true;
+        if let Some(hint) = &ipv6_dhcp.prefix_hint {
+            if nm_pd_hint {
+                ipconfig_result.extra.set("ipv6", "dhcp-pd-hint", hint);
+            } else {
+                ipconfig_result.findings.warn(
+                    Diagnostic::Ipv6DhcpMode,
+                    Some("ipv6_dhcp.prefix_hint"),
+                    format!(
+                        "The prefix hint {hint} of {} needs NetworkManager 1.44 or later",
+                        self.name
+                    ),
+                );
+            }
         }
-        if ipv6_dhcp.recover_lease != ipv6_dhcp_default.recover_lease {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv6_dhcp.recover_lease)
+        let settings = MIGRATION_SETTINGS.get().unwrap();
+        if settings.ipv6_shared.is_empty() {
+            ipconfig_result.findings.info(
+                Diagnostic::Ipv6DhcpMode,
+                Some("ipv6_dhcp.mode"),
+                format!(
+                    "NetworkManager only requests a delegated prefix on {} for downstream connections with ipv6 method shared, see --ipv6-shared",
+                    self.name
+                ),
             );
-            warnings = true;
         }
-        if ipv6_dhcp.refresh_lease != ipv6_dhcp_default.refresh_lease {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv6_dhcp.refresh_lease)
+    }
+
+    /// Maps the DHCP user class of `family` to `dhcp-user-class`.
+    ///
+    /// NetworkManager always sends it in the RFC 3004 (DHCPv4) respectively RFC 8415 (DHCPv6)
+    /// format with a single identifier.
+    fn add_dhcp_user_class(
+        &self,
+        family: &str,
+        user_class: &UserClass,
+        nm_user_class: bool,
+        ipconfig_result: &mut IpConfigResult,
+    ) {
+        let field = format!("{family}_dhcp.user_class");
+        if !nm_user_class {
+            ipconfig_result.findings.warn(
+                Diagnostic::DhcpUserClass,
+                Some(field.as_str()),
+                format!(
+                    "The DHCP user class of {} needs NetworkManager 1.38 or later",
+                    self.name
+                ),
             );
-            warnings = true;
+            return;
         }
-    }
-    if let Some(ipv6_auto) = &interface.ipv6_auto {
-        let ipv6_auto_default = Ipv6Auto::default();
-        if ipv6_auto.update != ipv6_auto_default.update {
-            log::warn!(
-                "Unhandled field in interface {}: {}",
-                interface.name,
-                stringify!(ipv6_auto.update)
+        let [identifier] = user_class.identifiers.as_slice() else {
+            ipconfig_result.findings.warn(
+                Diagnostic::DhcpUserClass,
+                Some(field.as_str()),
+                format!(
+                    "NetworkManager can only send a single DHCP user class identifier on {}",
+                    self.name
+                ),
+            );
+            return;
+        };
+        if family == "ipv4" && user_class.format.as_deref() == Some("string") {
+            ipconfig_result.findings.warn(
+                Diagnostic::DhcpUserClass,
+                Some(field.as_str()),
+                format!(
+                    "NetworkManager sends the DHCP user class of {} in RFC 3004 format instead of as plain string",
+                    self.name
+                ),
             );
-            warnings = true;
         }
+        ipconfig_result
+            .extra
+            .set(family, "dhcp-user-class", identifier);
     }
 
-    warnings
+    /// Maps the FQDN option settings to the NetworkManager hostname properties of `family`.
+    ///
+    /// DHCPv6 only knows the FQDN option, for DHCPv4 NetworkManager sends it when the hostname
+    /// is fully qualified or set as `dhcp-fqdn`.
+    fn add_dhcp_fqdn(
+        &self,
+        family: &str,
+        fqdn: &DhcpFqdn,
+        hostname: &mut Option<String>,
+        send_hostname: &mut Option<bool>,
+        ipconfig_result: &mut IpConfigResult,
+    ) {
+        let ipv4 = family == "ipv4";
+        let encode = ipv4 && fqdn.encode;
+        if fqdn.update != FqdnUpdate::Both || (ipv4 && !fqdn.encode) {
+            let mut flags = match fqdn.update {
+                FqdnUpdate::Both => NM_FQDN_SERV_UPDATE,
+                FqdnUpdate::Ptr => 0,
+                FqdnUpdate::None => NM_FQDN_NO_UPDATE,
+            };
+            if encode {
+                flags |= NM_FQDN_ENCODED;
+            }
+            if flags == 0 {
+                flags = NM_FQDN_CLEAR_FLAGS;
+            }
+            ipconfig_result
+                .extra
+                .set(family, "dhcp-hostname-flags", flags);
+        }
+
+        let qualified = hostname.as_ref().is_some_and(|h| h.contains('.'));
+        match fqdn.enabled {
+            FqdnEnabled::Default => {}
+            FqdnEnabled::Enable => {
+                if ipv4 {
+                    if let Some(hostname) = hostname.take() {
+                        ipconfig_result.extra.set("ipv4", "dhcp-fqdn", hostname);
+                    }
+                }
+                if fqdn.qualify && !qualified {
+                    ipconfig_result.findings.info(
+                        Diagnostic::DhcpFqdn,
+                        Some(format!("{family}_dhcp.fqdn.qualify").as_str()),
+                        format!(
+                            "NetworkManager doesn't qualify the DHCP hostname of {}",
+                            self.name
+                        ),
+                    );
+                }
+            }
+            FqdnEnabled::Disable if ipv4 => {
+                if qualified {
+                    ipconfig_result.findings.warn(
+                        Diagnostic::DhcpFqdn,
+                        Some("ipv4_dhcp.fqdn.enabled"),
+                        format!(
+                            "NetworkManager sends the fully qualified DHCP hostname of {} in the FQDN option",
+                            self.name
+                        ),
+                    );
+                }
+            }
+            FqdnEnabled::Disable => {
+                *hostname = None;
+                *send_hostname = Some(false);
+            }
+        }
+    }
+
+    /// Converts the wicked `client-id` into the NetworkManager value sending the same bytes.
+    fn nm_client_id(
+        &self,
+        ipv4_dhcp: &Ipv4Dhcp,
+        ipconfig_result: &mut IpConfigResult,
+    ) -> Option<String> {
+        let hw = if self.infiniband.is_some() || self.infiniband_child.is_some() {
+            ARPHRD_INFINIBAND
+        } else {
+            ARPHRD_ETHER
+        };
+        let value = ipv4_dhcp.client_id.as_deref()?;
+        let client_id = ClientId::parse(value, hw)?;
+        let nm_client_id = client_id.to_nm();
+        if nm_client_id.is_none() {
+            ipconfig_result.findings.warn(
+                Diagnostic::DhcpClientId,
+                Some("ipv4_dhcp.client_id"),
+                format!(
+                    "The DHCP client-id '{value}' of {} can't be migrated, NetworkManager sends a different one",
+                    self.name
+                ),
+            );
+        }
+        nm_client_id
+    }
+
+    /// NetworkManager handles router advertisements in userspace, also while forwarding, so
+    /// only disabling them needs a different method. Redirects are kept with a sysctl drop-in.
+    fn add_ra_settings(&self, method6: &mut Ipv6Method, ipconfig_result: &mut IpConfigResult) {
+        if self.ipv6.accept_ra == Some(AcceptRa::Disable) && *method6 == Ipv6Method::Auto {
+            let method = if self.ipv6_dhcp.is_some() {
+                *method6 = Ipv6Method::Dhcp;
+                "dhcp"
+            } else {
+                *method6 = Ipv6Method::LinkLocal;
+                "link-local"
+            };
+            ipconfig_result.findings.info(
+                Diagnostic::Ipv6AcceptRa,
+                Some("ipv6.accept-ra"),
+                format!(
+                    "Router advertisements are disabled on {}, using ipv6 method {method}",
+                    self.name
+                ),
+            );
+        }
+
+        if let Some(accept_redirects) = self.ipv6.accept_redirects {
+            ipconfig_result.extra.set_sysctl(
+                format!("net/ipv6/conf/{}/accept_redirects", self.name),
+                u8::from(accept_redirects),
+            );
+        }
+    }
+
+    /// NetworkManager always generates the SLAAC addresses itself, so only eui64 addresses
+    /// can be reproduced exactly.
+    fn add_addr_gen_mode(&self, ipconfig_result: &mut IpConfigResult) {
+        let findings = &mut ipconfig_result.findings;
+        let extra = &mut ipconfig_result.extra;
+        let field = Some("ipv6.addr-gen-mode");
+        match &self.ipv6.addr_gen_mode {
+            Some(AddrGenMode::Eui64) => extra.set("ipv6", "addr-gen-mode", "eui64"),
+            Some(AddrGenMode::StablePrivacy) => {
+                extra.set("ipv6", "addr-gen-mode", "stable-privacy");
+                findings.warn(
+                    Diagnostic::Ipv6AddrGenMode,
+                    field,
+                    format!(
+                        "NetworkManager generates the stable-privacy addresses of {} from its own secret key, the addresses will change",
+                        self.name
+                    ),
+                );
+            }
+            Some(AddrGenMode::Random) => {
+                // connection.stable-id=${RANDOM} would also randomize the cloned MAC address,
+                // DHCP client-id and DUID of stable modes, so it's left alone
+                extra.set("ipv6", "addr-gen-mode", "stable-privacy");
+                findings.warn(
+                    Diagnostic::Ipv6AddrGenMode,
+                    field,
+                    format!(
+                        "Random ipv6 addresses of {} aren't supported by NetworkManager, stable-privacy addresses are generated instead",
+                        self.name
+                    ),
+                );
+            }
+            Some(AddrGenMode::None) => findings.warn(
+                Diagnostic::Ipv6AddrGenMode,
+                field,
+                format!(
+                    "addr-gen-mode none of {} isn't supported by NetworkManager, link-local addresses will be generated",
+                    self.name
+                ),
+            ),
+            None => {}
+        }
+
+        if self.ipv6.stable_secret.is_some() {
+            findings.info(
+                Diagnostic::Ipv6AddrGenMode,
+                Some("ipv6.stable-secret"),
+                format!(
+                    "The ipv6 stable-secret of {} can't be migrated, NetworkManager uses /var/lib/NetworkManager/secret_key",
+                    self.name
+                ),
+            );
+        }
+    }
+
+    fn add_routes(
+        &self,
+        family: &str,
+        routes: &[Route],
+        ip_routes: &mut Vec<IpRoute>,
+        attributes: &mut Vec<RouteAttributes>,
+        findings: &mut Findings,
+    ) {
+        let field = format!("{family}_static.route");
+        let nm_weight = MIGRATION_SETTINGS
+            .get()
+            .unwrap()
+            .nm_version
+            .is_some_and(|version| version >= NM_ROUTE_WEIGHT_VERSION);
+        for route in routes {
+            for nexthop in route.nexthops.iter().flatten() {
+                if let Some(device) = nexthop.device.as_ref().filter(|d| **d != self.name) {
+                    findings.warn(
+                        Diagnostic::MultipathDevice,
+                        Some(field.as_str()),
+                        format!(
+                            "Nexthop {} of {family} route for {} is on device {device}, NetworkManager only adds it on {}",
+                            nexthop.gateway, self.name, self.name
+                        ),
+                    );
+                }
+            }
+            if let Some(protocol) = route
+                .kern
+                .as_ref()
+                .and_then(|k| k.protocol.as_deref())
+                .filter(|p| !["boot", "static"].contains(p))
+            {
+                findings.info(
+                    Diagnostic::RouteProtocol,
+                    Some(field.as_str()),
+                    format!(
+                        "Route protocol {protocol} for {} isn't supported by NetworkManager, the route uses protocol static",
+                        self.name
+                    ),
+                );
+            }
+            if family == "ipv6"
+                && route.nexthops.as_ref().is_some_and(|n| n.len() > 1)
+                && route
+                    .nexthops
+                    .iter()
+                    .flatten()
+                    .any(|n| n.weight.is_some_and(|w| w != 1))
+            {
+                findings.info(
+                    Diagnostic::MultipathWeight,
+                    Some(field.as_str()),
+                    format!(
+                        "Nexthop weights of ipv6 multipath routes for {} aren't supported by NetworkManager, all nexthops get the same weight",
+                        self.name
+                    ),
+                );
+            }
+            if family == "ipv4" && !nm_weight {
+                if let [first, _, ..] = route.nexthops.as_deref().unwrap_or_default() {
+                    findings.warn(
+                        Diagnostic::MultipathWeight,
+                        Some(field.as_str()),
+                        format!(
+                            "Ipv4 multipath routes need NetworkManager {}.{}, only nexthop {} of the route for {} is migrated",
+                            NM_ROUTE_WEIGHT_VERSION.0, NM_ROUTE_WEIGHT_VERSION.1, first.gateway, self.name
+                        ),
+                    );
+                }
+            }
+            match route.to_ip_routes(nm_weight) {
+                Ok(converted) => {
+                    for (ip_route, route_attributes) in converted {
+                        ip_routes.push(ip_route);
+                        attributes.push(route_attributes);
+                    }
+                }
+                Err(e) => findings.warn(
+                    Diagnostic::InvalidRoute,
+                    Some(field.as_str()),
+                    format!("Failed parsing {family} route for {}: {}", self.name, e),
+                ),
+            }
+        }
+    }
+}
+
+impl Route {
+    /// Converts the route into NetworkManager routes, a multipath route becomes one route per
+    /// nexthop which are combined into an ECMP route by their `weight`.
+    ///
+    /// Without `nm_weight`, i.e. NetworkManager doesn't support the `weight` attribute, only the
+    /// first nexthop of ipv4 multipath routes is used.
+    pub fn to_ip_routes(
+        &self,
+        nm_weight: bool,
+    ) -> Result<Vec<(IpRoute, RouteAttributes)>, anyhow::Error> {
+        let nexthops = self.nexthops.as_deref().unwrap_or_default();
+        let mut gateways: Vec<IpAddr> = vec![];
+        for nexthop in nexthops {
+            gateways.push(IpAddr::from_str(&nexthop.gateway)?);
+        }
+        if !nm_weight && gateways.first().is_some_and(IpAddr::is_ipv4) {
+            gateways.truncate(1);
+        }
+        let destination = if let Some(destination) = &self.destination {
+            IpInet::from_str(destination)?
+        } else if let Some(next_hop) = gateways.first() {
+            // default route
+            let default_ip = if next_hop.is_ipv4() {
+                IpAddr::from_str("0.0.0.0")?
+            } else {
+                IpAddr::from_str("::")?
+            };
+            IpInet::new(default_ip, 0)?
+        } else {
+            return Err(anyhow::anyhow!(
+                "Route contains neither destination nor next-hop"
+            ));
+        };
+        let metric = self.priority;
+        let attributes = self.attributes(destination.is_ipv4())?;
+
+        if gateways.is_empty() {
+            let route = IpRoute {
+                destination,
+                next_hop: None,
+                metric,
+            };
+            return Ok(vec![(route, attributes)]);
+        }
+
+        let multipath = gateways.len() > 1;
+        Ok(nexthops
+            .iter()
+            .zip(gateways)
+            .map(|(nexthop, gateway)| {
+                let mut attributes = attributes.clone();
+                if nexthop
+                    .flags
+                    .as_deref()
+                    .is_some_and(|f| f.split(',').any(|f| f.trim() == "onlink"))
+                {
+                    attributes.insert("onlink".to_string(), "true".to_string());
+                }
+                // NetworkManager only merges ipv4 routes with a weight into a multipath route,
+                // the kernel merges ipv6 routes with the same destination and metric by itself
+                if multipath && gateway.is_ipv4() {
+                    attributes.insert(
+                        "weight".to_string(),
+                        nexthop.weight.unwrap_or(1).to_string(),
+                    );
+                }
+                let route = IpRoute {
+                    destination,
+                    next_hop: Some(gateway),
+                    metric,
+                };
+                (route, attributes)
+            })
+            .collect())
+    }
+
+    /// NetworkManager attributes shared by all nexthops of the route.
+    fn attributes(&self, ipv4: bool) -> Result<RouteAttributes, anyhow::Error> {
+        let mut attributes = RouteAttributes::new();
+        let mut set = |key: &str, value: String| attributes.insert(key.to_string(), value);
+
+        if let Some(kern) = &self.kern {
+            if let Some(table) = &kern.table {
+                let settings = MIGRATION_SETTINGS.get().unwrap();
+                let table = parse_route_table(table, &settings.iproute2_dir)?;
+                if table != RT_TABLE_MAIN {
+                    set("table", table.to_string());
+                }
+            }
+            if let Some(route_type) = &kern.route_type {
+                match route_type.as_str() {
+                    "unicast" => {}
+                    "local" | "blackhole" | "unreachable" | "prohibit" | "throw" => {
+                        set("type", route_type.clone());
+                    }
+                    route_type => {
+                        anyhow::bail!("Route type {route_type} isn't supported by NetworkManager")
+                    }
+                }
+            }
+            if let Some(scope) = &kern.scope {
+                let scope = parse_route_scope(scope)?;
+                if scope != 0 {
+                    if !ipv4 {
+                        anyhow::bail!("Route scope is only supported for ipv4 routes");
+                    }
+                    set("scope", scope.to_string());
+                }
+            }
+        }
+        if let Some(source) = &self.pref_source {
+            set("src", IpAddr::from_str(source)?.to_string());
+        }
+        if let Some(metrics) = &self.metrics {
+            let values = [
+                ("mtu", metrics.mtu),
+                ("window", metrics.window),
+                ("initcwnd", metrics.initcwnd),
+            ];
+            for (key, value) in values {
+                if let Some(value) = value {
+                    set(key, value.to_string());
+                }
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+impl Rule {
+    /// Renders the rule in the `routing-rules` syntax of NetworkManager.
+    pub fn to_nm_rule(&self) -> Result<String, anyhow::Error> {
+        let Some(priority) = self.priority else {
+            anyhow::bail!("Routing rules without priority aren't supported by NetworkManager");
+        };
+        let selector = &self.selector;
+        let mut parts = vec![];
+        if selector.invert {
+            parts.push("not".to_string());
+        }
+        parts.push(format!("priority {priority}"));
+        for (key, prefix) in [("from", &selector.from), ("to", &selector.to)] {
+            if let Some(prefix) = prefix.as_deref().filter(|p| *p != "all") {
+                let prefix = IpInet::from_str(prefix)?;
+                parts.push(format!(
+                    "{key} {}/{}",
+                    prefix.address(),
+                    prefix.network_length()
+                ));
+            }
+        }
+        if let Some(tos) = &selector.tos {
+            parts.push(format!("tos {tos}"));
+        }
+        if let Some(fwmark) = &selector.fwmark {
+            match &selector.fwmask {
+                Some(fwmask) => parts.push(format!("fwmark {fwmark}/{fwmask}")),
+                None => parts.push(format!("fwmark {fwmark}")),
+            }
+        }
+        if let Some(iif) = &selector.iif {
+            parts.push(format!("iif {iif}"));
+        }
+        if let Some(oif) = &selector.oif {
+            parts.push(format!("oif {oif}"));
+        }
+        match self.action.action_type.as_deref() {
+            None | Some("table") => {
+                let settings = MIGRATION_SETTINGS.get().unwrap();
+                let table = parse_route_table(
+                    self.action.table.as_deref().unwrap_or("main"),
+                    &settings.iproute2_dir,
+                )?;
+                parts.push(format!("table {table}"));
+            }
+            Some(action @ ("blackhole" | "unreachable" | "prohibit")) => {
+                parts.push(format!("type {action}"));
+            }
+            Some(action) => {
+                anyhow::bail!("Routing rule action {action} isn't supported by NetworkManager")
+            }
+        }
+        Ok(parts.join(" "))
+    }
+}
+
+const RT_TABLE_MAIN: u32 = 254;
+
+/// Resolves a routing table name like `main` or one defined in `rt_tables` of the iproute2
+/// directory `dir` (including its `rt_tables.d/*.conf`) to its id.
+pub fn parse_route_table(table: &str, dir: &Path) -> Result<u32, anyhow::Error> {
+    if let Ok(id) = table.parse::<u32>() {
+        return Ok(id);
+    }
+    match table {
+        "unspec" => return Ok(0),
+        "default" => return Ok(253),
+        "main" => return Ok(RT_TABLE_MAIN),
+        "local" => return Ok(255),
+        _ => {}
+    }
+    let mut paths = vec![dir.join("rt_tables")];
+    if let Ok(entries) = std::fs::read_dir(dir.join("rt_tables.d")) {
+        let mut dropins: Vec<PathBuf> = entries
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "conf"))
+            .collect();
+        dropins.sort();
+        paths.extend(dropins);
+    }
+    for path in paths {
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in contents.lines() {
+            let mut columns = line
+                .split('#')
+                .next()
+                .unwrap_or_default()
+                .split_whitespace();
+            if let (Some(id), Some(name)) = (columns.next(), columns.next()) {
+                if name == table {
+                    return id.parse().with_context(|| {
+                        format!(
+                            "Invalid id {id} of routing table {table} in {}",
+                            path.display()
+                        )
+                    });
+                }
+            }
+        }
+    }
+    anyhow::bail!(
+        "Unknown routing table {table}, it isn't defined in {}",
+        dir.join("rt_tables").display()
+    )
+}
+
+fn parse_route_scope(scope: &str) -> Result<u8, anyhow::Error> {
+    Ok(match scope {
+        "universe" | "global" => 0,
+        "site" => 200,
+        "link" => 253,
+        "host" => 254,
+        "nowhere" => 255,
+        scope => scope
+            .parse()
+            .map_err(|_| anyhow::anyhow!("Unknown route scope {scope}"))?,
+    })
+}
+
+/// DHCP `flags` that are migrated, `primary` only together with ipv4:auto.
+const MAPPED_DHCP_FLAGS: &[&str] = &["group", "required", "optional"];
+
+/// Maps the DHCP requirement flags and defer timeout of one address family.
+///
+/// `required` leases must succeed (may-fail=no), while `group` leases are waited for up to the
+/// defer timeout even when the other family finished first (required-timeout). Without a defer
+/// timeout wicked keeps waiting for the lease, so the DHCP timeout becomes infinite.
+fn add_dhcp_timing(family: &str, flags: &str, defer_timeout: u32, extra: &mut ExtraSettings) {
+    let flags: HashSet<&str> = flags.split(',').map(|f| f.trim()).collect();
+    if flags.contains("required") {
+        extra.set(family, "may-fail", "false");
+    }
+    if defer_timeout == default_defer_timeout() {
+        return;
+    }
+    // Both timeouts are int32 properties, larger values are as good as infinite
+    let max = i32::MAX as u32;
+    if defer_timeout == 0 {
+        extra.set(family, "dhcp-timeout", max);
+    } else {
+        extra.set(family, "dhcp-timeout", defer_timeout.min(max));
+        if flags.contains("group") && !flags.contains("required") {
+            extra.set(
+                family,
+                "required-timeout",
+                defer_timeout.saturating_mul(1000).min(max),
+            );
+        }
+    }
+}
+
+/// Bits of `dhcp-hostname-flags`
+const NM_FQDN_SERV_UPDATE: u32 = 0x1;
+const NM_FQDN_ENCODED: u32 = 0x2;
+const NM_FQDN_NO_UPDATE: u32 = 0x4;
+const NM_FQDN_CLEAR_FLAGS: u32 = 0x8;
+
+/// Whether `flags` only contains DHCP flags that are migrated.
+fn has_only_mapped_dhcp_flags(flags: &str, with_auto: bool) -> bool {
+    flags
+        .split(',')
+        .map(|f| f.trim())
+        .filter(|f| !f.is_empty())
+        .all(|f| MAPPED_DHCP_FLAGS.contains(&f) || (with_auto && f == "primary"))
+}
+
+/// Entries of `update` that are migrated to NetworkManager properties.
+const MAPPED_UPDATES: &[&str] = &["default-route", "dns", "hostname", "mtu"];
+
+/// Entries of `update` NetworkManager never applies, with what they configure.
+const UNSUPPORTED_UPDATES: &[(&str, &str)] = &[
+    ("nis", "NIS servers"),
+    ("ntp", "NTP servers"),
+    ("tz", "the timezone"),
+    ("boot", "boot server settings"),
+];
+
+/// Entries of the default `update` list that are missing in `update`.
+fn dropped_updates(update: &str, default_update: &str) -> HashSet<String> {
+    let update_set: HashSet<&str> = update.split(',').map(|s| s.trim()).collect();
+    default_update
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && !update_set.contains(s))
+        .map(String::from)
+        .collect()
+}
+
+fn check_dhcp_update(
+    update: &str,
+    default_update: &str,
+    interface_name: &str,
+    dhcp_type: &str,
+    findings: &mut Findings,
+) {
+    let default_set: HashSet<&str> = default_update
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && !MAPPED_UPDATES.contains(s))
+        .collect();
+
+    let update_set: HashSet<&str> = update
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty() && !MAPPED_UPDATES.contains(s))
+        .collect();
+
+    for missing in default_set.difference(&update_set) {
+        report_dhcp_update(
+            format!(
+                "{dhcp_type} in interface {interface_name} is missing default entry: {missing}"
+            ),
+            missing,
+            dhcp_type,
+            findings,
+        );
+    }
+
+    for added in update_set.difference(&default_set) {
+        report_dhcp_update(
+            format!("{dhcp_type} in interface {interface_name} has extra entry: {added}"),
+            added,
+            dhcp_type,
+            findings,
+        );
+    }
+}
+
+/// Changed entries NetworkManager doesn't support are warnings, so they can be denied.
+fn report_dhcp_update(message: String, entry: &str, dhcp_type: &str, findings: &mut Findings) {
+    match UNSUPPORTED_UPDATES.iter().find(|(e, _)| *e == entry) {
+        Some((_, what)) => findings.warn(
+            Diagnostic::DhcpUpdate,
+            Some(dhcp_type),
+            format!("{message}, NetworkManager doesn't apply {what} from DHCP"),
+        ),
+        None => findings.info(Diagnostic::DhcpUpdate, Some(dhcp_type), message),
+    }
+}
+
+fn info_recover_lease(findings: &mut Findings, interface_name: &str, field: &str) {
+    findings.info(
+        Diagnostic::DhcpRecoverLease,
+        Some(field),
+        format!("NetworkManager always tries to reuse the previous DHCP lease of {interface_name}"),
+    );
+}
+
+fn warn_unhandled(findings: &mut Findings, interface_name: &str, field: &str) {
+    findings.warn(
+        Diagnostic::UnhandledField,
+        Some(field),
+        format!("Unhandled field in interface {interface_name}: {field}"),
+    );
+}
+
+fn check_unhandled_fields(interface: &Interface) -> Findings {
+    let mut findings = Findings::default();
+
+    if let Some(ipv4_dhcp) = &interface.ipv4_dhcp {
+        let ipv4_dhcp_default = Ipv4Dhcp::default();
+        if !has_only_mapped_dhcp_flags(&ipv4_dhcp.flags, interface.ipv4_auto.is_some()) {
+            warn_unhandled(&mut findings, &interface.name, stringify!(ipv4_dhcp.flags));
+        }
+        check_dhcp_update(
+            &ipv4_dhcp.update,
+            &ipv4_dhcp_default.update,
+            &interface.name,
+            "ipv4_dhcp.update",
+            &mut findings,
+        );
+        if !ipv4_dhcp.recover_lease {
+            info_recover_lease(&mut findings, &interface.name, "ipv4_dhcp.recover_lease");
+        }
+        if ipv4_dhcp.lease_time.is_some() {
+            warn_unhandled(
+                &mut findings,
+                &interface.name,
+                stringify!(ipv4_dhcp.lease_time),
+            );
+        }
+    }
+
+    if let Some(ipv6_dhcp) = &interface.ipv6_dhcp {
+        let ipv6_dhcp_default = Ipv6Dhcp::default();
+        if !has_only_mapped_dhcp_flags(&ipv6_dhcp.flags, false) {
+            warn_unhandled(&mut findings, &interface.name, stringify!(ipv6_dhcp.flags));
+        }
+
+        check_dhcp_update(
+            &ipv6_dhcp.update,
+            &ipv6_dhcp_default.update,
+            &interface.name,
+            "ipv6_dhcp.update",
+            &mut findings,
+        );
+        if ipv6_dhcp.modes().any(|m| !IPV6_DHCP_MODES.contains(&m)) {
+            warn_unhandled(&mut findings, &interface.name, stringify!(ipv6_dhcp.mode));
+        }
+        if !ipv6_dhcp.rapid_commit {
+            findings.warn(
+                Diagnostic::Ipv6DhcpMode,
+                Some(stringify!(ipv6_dhcp.rapid_commit)),
+                format!(
+                    "NetworkManager can't disable DHCPv6 rapid commit on {}",
+                    interface.name
+                ),
+            );
+        }
+        if !ipv6_dhcp.recover_lease {
+            info_recover_lease(&mut findings, &interface.name, "ipv6_dhcp.recover_lease");
+        }
+        if ipv6_dhcp.vendor_class.is_some() {
+            findings.warn(
+                Diagnostic::DhcpVendorClass,
+                Some(stringify!(ipv6_dhcp.vendor_class)),
+                format!(
+                    "NetworkManager can't send a DHCPv6 vendor class on {}",
+                    interface.name
+                ),
+            );
+        }
+        if ipv6_dhcp.lease_time.is_some() {
+            warn_unhandled(
+                &mut findings,
+                &interface.name,
+                stringify!(ipv6_dhcp.lease_time),
+            );
+        }
+        if ipv6_dhcp.refresh_lease != ipv6_dhcp_default.refresh_lease {
+            warn_unhandled(
+                &mut findings,
+                &interface.name,
+                stringify!(ipv6_dhcp.refresh_lease),
+            );
+        }
+    }
+    if let Some(ipv6_auto) = &interface.ipv6_auto {
+        check_dhcp_update(
+            &ipv6_auto.update,
+            &default_v6_auto_update(),
+            &interface.name,
+            "ipv6_auto.update",
+            &mut findings,
+        );
+    }
+
+    findings
+}
+
+/// Derives the uuid of a connection from its id and type, e.g. `eth0-port` and `ovs-port`.
+fn stable_uuid(connection: &model::Connection) -> Uuid {
+    let name = format!("{}/{}", connection_type(&connection.config), connection.id);
+    Uuid::new_v5(&CONNECTION_UUID_NAMESPACE, name.as_bytes())
+}
+
+/// Replaces the random uuids of the connections of one interface by stable ones.
+fn stable_uuids(connections: &mut [model::Connection]) {
+    let uuids: HashMap<Uuid, Uuid> = connections
+        .iter()
+        .map(|c| (c.uuid, stable_uuid(c)))
+        .collect();
+    for connection in connections.iter_mut() {
+        connection.uuid = uuids[&connection.uuid];
+        if let Some(uuid) = connection.controller.and_then(|c| uuids.get(&c)) {
+            connection.controller = Some(*uuid);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::report::FindingLevel;
     use log::Level;
 
     #[allow(dead_code)]
@@ -1048,6 +2179,36 @@ mod tests {
         let _ = MIGRATION_SETTINGS.set(crate::MigrationSettings::default());
     }
 
+    #[test]
+    fn test_stable_uuids() {
+        let port = model::Connection {
+            id: "ovsbr0-port".to_string(),
+            config: model::ConnectionConfig::OvsPort(model::OvsPortConfig::default()),
+            ..Default::default()
+        };
+        let interface = model::Connection {
+            id: "ovsbr0".to_string(),
+            controller: Some(port.uuid),
+            ..Default::default()
+        };
+        let mut connections = vec![port.clone(), interface.clone()];
+        stable_uuids(&mut connections);
+
+        assert_eq!(connections[0].uuid, stable_uuid(&port));
+        assert_eq!(connections[1].uuid, stable_uuid(&interface));
+        assert_eq!(connections[1].controller, Some(connections[0].uuid));
+        assert_ne!(connections[0].uuid, connections[1].uuid);
+        assert_eq!(connections[0].uuid.get_version_num(), 5);
+
+        // Same id with another type gets another uuid
+        let dummy = model::Connection {
+            id: "ovsbr0".to_string(),
+            config: model::ConnectionConfig::Dummy,
+            ..Default::default()
+        };
+        assert_ne!(stable_uuid(&dummy), connections[1].uuid);
+    }
+
     #[test]
     fn test_static_interface_to_connection() {
         setup_default_migration_settings();
@@ -1061,6 +2222,7 @@ mod tests {
                 routes: Some(vec![Route {
                     nexthops: Some(vec![Nexthop {
                         gateway: "127.0.0.1".to_string(),
+                        ..Default::default()
                     }]),
                     ..Default::default()
                 }]),
@@ -1074,6 +2236,7 @@ mod tests {
                 routes: Some(vec![Route {
                     nexthops: Some(vec![Nexthop {
                         gateway: "::1".to_string(),
+                        ..Default::default()
                     }]),
                     ..Default::default()
                 }]),
@@ -1132,6 +2295,371 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_multipath_route_to_connection() {
+        setup_default_migration_settings();
+        let nexthop = |gateway: &str, device: Option<&str>, weight: Option<u32>| Nexthop {
+            gateway: gateway.to_string(),
+            device: device.map(String::from),
+            weight,
+        };
+        let interface = Interface {
+            name: "eth0".to_string(),
+            ipv4_static: Some(Ipv4Static {
+                addresses: None,
+                routes: Some(vec![
+                    Route {
+                        destination: Some("10.0.0.0/8".to_string()),
+                        ..Default::default()
+                    },
+                    Route {
+                        nexthops: Some(vec![
+                            nexthop("192.168.1.1", Some("eth0"), Some(3)),
+                            nexthop("192.168.1.2", None, None),
+                        ]),
+                        priority: Some(5),
+                        ..Default::default()
+                    },
+                ]),
+            }),
+            ipv6_static: Some(Ipv6Static {
+                addresses: None,
+                routes: Some(vec![Route {
+                    nexthops: Some(vec![
+                        nexthop("2001:db8::1", None, None),
+                        nexthop("2001:db8::2", Some("eth1"), None),
+                    ]),
+                    ..Default::default()
+                }]),
+            }),
+            ..Default::default()
+        };
+
+        // Without a NetworkManager supporting the weight only the first ipv4 nexthop is kept
+        let result = interface.to_connection(&None).unwrap();
+        let ip_config = &result.connections[0].ip_config;
+        assert_eq!(ip_config.routes4.len(), 2);
+        assert_eq!(
+            ip_config.routes4[1].next_hop,
+            Some("192.168.1.1".parse().unwrap())
+        );
+        assert_eq!(ip_config.routes4[1].metric, Some(5));
+        assert_eq!(ip_config.routes6.len(), 2);
+        assert!(result
+            .findings
+            .iter()
+            .any(|f| f.code == Diagnostic::MultipathWeight && f.level == FindingLevel::Warning));
+
+        let extra = &result.extra_settings["eth0"];
+        assert!(extra
+            .route_attributes4
+            .iter()
+            .all(|a| a.get("weight").is_none()));
+        assert!(extra.route_attributes6.iter().all(|a| a.is_empty()));
+
+        let multipath = &interface
+            .ipv4_static
+            .as_ref()
+            .unwrap()
+            .routes
+            .as_ref()
+            .unwrap()[1];
+        let routes = multipath.to_ip_routes(true).unwrap();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[1].0.next_hop, Some("192.168.1.2".parse().unwrap()));
+        let weights: Vec<Option<&String>> = routes.iter().map(|(_, a)| a.get("weight")).collect();
+        assert_eq!(
+            weights,
+            vec![Some(&"3".to_string()), Some(&"1".to_string())]
+        );
+
+        let devices: Vec<&str> = result
+            .findings
+            .iter()
+            .filter(|f| f.code == Diagnostic::MultipathDevice)
+            .map(|f| f.message.as_str())
+            .collect();
+        assert_eq!(devices.len(), 1);
+        assert!(devices[0].contains("2001:db8::2"));
+    }
+
+    #[test]
+    fn test_route_attributes() {
+        setup_default_migration_settings();
+        let route = Route {
+            destination: Some("10.10.0.0/16".to_string()),
+            nexthops: Some(vec![Nexthop {
+                gateway: "192.168.1.1".to_string(),
+                flags: Some("onlink".to_string()),
+                ..Default::default()
+            }]),
+            pref_source: Some("192.168.1.5".to_string()),
+            kern: Some(RouteKern {
+                table: Some("100".to_string()),
+                route_type: Some("unicast".to_string()),
+                scope: Some("link".to_string()),
+                protocol: Some("boot".to_string()),
+            }),
+            metrics: Some(RouteMetrics {
+                mtu: Some(1400),
+                window: None,
+                initcwnd: Some(10),
+            }),
+            ..Default::default()
+        };
+        let routes = route.to_ip_routes(true).unwrap();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(
+            crate::extra::keyfile_route_options(&routes[0].1),
+            "initcwnd=10,mtu=1400,onlink=true,scope=253,src=192.168.1.5,table=100"
+        );
+
+        let blackhole = Route {
+            destination: Some("10.20.0.0/16".to_string()),
+            kern: Some(RouteKern {
+                table: Some("main".to_string()),
+                route_type: Some("blackhole".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let routes = blackhole.to_ip_routes(true).unwrap();
+        assert_eq!(routes[0].0.next_hop, None);
+        assert_eq!(
+            crate::extra::keyfile_route_options(&routes[0].1),
+            "type=blackhole"
+        );
+
+        let nat = Route {
+            destination: Some("10.30.0.0/16".to_string()),
+            kern: Some(RouteKern {
+                route_type: Some("nat".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(nat.to_ip_routes(true).is_err());
+    }
+
+    #[test]
+    fn test_parse_route_table() {
+        let dir = std::env::temp_dir().join(format!("wicked2nm-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("rt_tables.d")).unwrap();
+        std::fs::write(
+            dir.join("rt_tables"),
+            "255\tlocal\n254\tmain\n# 1\tinr.ruhep\n100 uplink # comment\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("rt_tables.d/vpn.conf"), "200 vpn\n").unwrap();
+        std::fs::write(dir.join("rt_tables.d/other.txt"), "201 other\n").unwrap();
+
+        assert_eq!(parse_route_table("42", &dir).unwrap(), 42);
+        assert_eq!(parse_route_table("main", &dir).unwrap(), 254);
+        assert_eq!(parse_route_table("uplink", &dir).unwrap(), 100);
+        assert_eq!(parse_route_table("vpn", &dir).unwrap(), 200);
+        assert!(parse_route_table("inr.ruhep", &dir).is_err());
+        assert!(parse_route_table("other", &dir).is_err());
+        // The host's rt_tables isn't consulted
+        assert!(parse_route_table("uplink", &dir.join("missing")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_dhcp_updates() {
+        setup_default_migration_settings();
+        let mut interface = Interface {
+            name: "eth0".to_string(),
+            ipv4_dhcp: Some(Ipv4Dhcp {
+                update: "nis,ntp,mtu".to_string(),
+                ..Default::default()
+            }),
+            ipv6_dhcp: Some(Ipv6Dhcp {
+                update: "dns,ntp".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let result = interface.to_ip_config(&None).unwrap();
+        let properties = &result.extra.properties;
+        assert_eq!(result.ip_config.never_default4, Some(true));
+        assert_eq!(result.ip_config.never_default6, Some(false));
+        assert_eq!(
+            properties[&("ipv4".to_string(), "ignore-auto-dns".to_string())],
+            "true"
+        );
+        assert!(!properties.contains_key(&("ipv6".to_string(), "ignore-auto-dns".to_string())));
+        assert_eq!(
+            properties[&("hostname".to_string(), "from-dhcp".to_string())],
+            "0"
+        );
+
+        interface.ipv6_dhcp.as_mut().unwrap().update = "hostname".to_string();
+        interface.ipv6_auto = Some(Ipv6Auto {
+            update: "".to_string(),
+            ..Default::default()
+        });
+        let result = interface.to_ip_config(&None).unwrap();
+        let properties = &result.extra.properties;
+        assert_eq!(
+            properties[&("ipv6".to_string(), "ignore-auto-dns".to_string())],
+            "true"
+        );
+        assert!(!properties.contains_key(&("hostname".to_string(), "from-dhcp".to_string())));
+        assert_eq!(result.findings.iter().count(), 1);
+
+        // The DHCP MTU is only ignored with a configured MTU
+        interface.ipv4_dhcp.as_mut().unwrap().update = "dns,hostname".to_string();
+        let result = interface.to_ip_config(&None).unwrap();
+        assert!(result
+            .findings
+            .iter()
+            .any(|f| f.code == Diagnostic::DhcpMtu));
+        interface.link.mtu = Some(1400);
+        let result = interface.to_ip_config(&None).unwrap();
+        assert!(!result
+            .findings
+            .iter()
+            .any(|f| f.code == Diagnostic::DhcpMtu));
+
+        // Unsupported entries are warnings, others infos
+        let mut findings = Findings::default();
+        check_dhcp_update(
+            "default-route,hostname,dns,ntp,mtu,tz,boot",
+            &default_v4_update(),
+            "eth0",
+            "ipv4_dhcp.update",
+            &mut findings,
+        );
+        let levels: Vec<_> = findings.iter().map(|f| f.level).collect();
+        assert_eq!(levels.len(), 2);
+        assert!(levels.contains(&FindingLevel::Warning));
+        assert!(levels.contains(&FindingLevel::Info));
+    }
+
+    #[test]
+    fn test_dad() {
+        let mut interface = Interface {
+            name: "eth0".to_string(),
+            ipv4: Ipv4 {
+                arp_verify: Some(false),
+                ..Default::default()
+            },
+            ipv6: Ipv6 {
+                accept_dad: Some("fail-protocol".parse().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut result = IpConfigResult::default();
+        interface.add_dad(&mut result);
+        assert_eq!(
+            result.extra.properties[&("ipv4".to_string(), "dad-timeout".to_string())],
+            "0"
+        );
+        assert_eq!(result.extra.sysctls["net/ipv6/conf/eth0/accept_dad"], "2");
+
+        interface.ipv4.arp_verify = Some(true);
+        interface.ipv6.accept_dad = Some(AcceptDad::FailAddress);
+        let mut result = IpConfigResult::default();
+        interface.add_dad(&mut result);
+        assert!(result.extra.is_empty());
+    }
+
+    #[test]
+    fn test_forwarding() {
+        let interface = Interface {
+            name: "eth0".to_string(),
+            ipv4: Ipv4 {
+                forwarding: Some(true),
+                ..Default::default()
+            },
+            ipv6: Ipv6 {
+                forwarding: Some(false),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut result = IpConfigResult::default();
+        interface.add_forwarding(true, &mut result);
+        assert_eq!(
+            result.extra.properties[&("ipv4".to_string(), "forwarding".to_string())],
+            "1"
+        );
+        assert_eq!(result.extra.sysctls.len(), 1);
+        assert_eq!(result.extra.sysctls["net/ipv6/conf/eth0/forwarding"], "0");
+
+        let mut result = IpConfigResult::default();
+        interface.add_forwarding(false, &mut result);
+        assert!(result.extra.properties.is_empty());
+        assert_eq!(result.extra.sysctls["net/ipv4/conf/eth0/forwarding"], "1");
+    }
+
+    #[test]
+    fn test_ra_settings() {
+        setup_default_migration_settings();
+        let mut interface = Interface {
+            name: "eth0".to_string(),
+            ipv6: Ipv6 {
+                accept_ra: Some(AcceptRa::Router),
+                accept_redirects: Some(false),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = interface.to_ip_config(&None).unwrap();
+        assert_eq!(result.ip_config.method6, Some(Ipv6Method::Auto));
+        assert_eq!(
+            result.extra.sysctls["net/ipv6/conf/eth0/accept_redirects"],
+            "0"
+        );
+
+        interface.ipv6.accept_ra = Some("0".parse().unwrap());
+        let result = interface.to_ip_config(&None).unwrap();
+        assert_eq!(result.ip_config.method6, Some(Ipv6Method::LinkLocal));
+
+        interface.ipv6_dhcp = Some(Ipv6Dhcp::default());
+        let result = interface.to_ip_config(&None).unwrap();
+        assert_eq!(result.ip_config.method6, Some(Ipv6Method::Dhcp));
+    }
+
+    #[test]
+    fn test_addr_gen_mode() {
+        setup_default_migration_settings();
+        let mut interface = Interface {
+            name: "eth0".to_string(),
+            ipv6: Ipv6 {
+                addr_gen_mode: Some(AddrGenMode::Eui64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = interface.to_ip_config(&None).unwrap();
+        assert_eq!(
+            result.extra.properties[&("ipv6".to_string(), "addr-gen-mode".to_string())],
+            "eui64"
+        );
+        assert!(!result.findings.has_warnings());
+
+        interface.ipv6.addr_gen_mode = Some("3".parse().unwrap());
+        interface.ipv6.stable_secret = Some("fe:dead::beaf".to_string());
+        let result = interface.to_ip_config(&None).unwrap();
+        assert_eq!(
+            result.extra.properties[&("ipv6".to_string(), "addr-gen-mode".to_string())],
+            "stable-privacy"
+        );
+        assert!(!result
+            .extra
+            .properties
+            .contains_key(&("connection".to_string(), "stable-id".to_string())));
+        assert!(result.findings.has_warnings());
+
+        interface.ipv6.addr_gen_mode = Some(AddrGenMode::StablePrivacy);
+        let result = interface.to_ip_config(&None).unwrap();
+        assert!(result.findings.has_warnings());
+    }
+
     #[test]
     fn test_dhcp_interface_to_connection() {
         setup_default_migration_settings();
@@ -1169,7 +2697,22 @@ mod tests {
             (
                 CreateCid::Rfc4361,
                 Some("52:54:00:dc:8e:94".to_string()),
-                DhcpClientId::Id("52:54:00:dc:8e:94".to_string()),
+                DhcpClientId::Id("01:52:54:00:dc:8e:94".to_string()),
+            ),
+            (
+                CreateCid::Rfc2132,
+                Some("ff:00:00:00:01:00:03:00:01:52:54:00:12:34:56".to_string()),
+                DhcpClientId::Id("ff:00:00:00:01:00:03:00:01:52:54:00:12:34:56".to_string()),
+            ),
+            (
+                CreateCid::Rfc2132,
+                Some("duid".to_string()),
+                DhcpClientId::Id("00:64:75:69:64".to_string()),
+            ),
+            (
+                CreateCid::Rfc2132,
+                Some("ab".to_string()),
+                DhcpClientId::Mac,
             ),
         ];
 
@@ -1300,7 +2843,7 @@ mod tests {
     #[test]
     fn test_ignored_default() {
         let ifc = Interface::default();
-        assert!(!has_unhandled_field(&ifc));
+        assert!(!check_unhandled_fields(&ifc).has_warnings());
 
         testing_logger::setup();
         let ifc = Interface {
@@ -1326,7 +2869,7 @@ mod tests {
             }),
             ..Default::default()
         };
-        assert!(has_unhandled_field(&ifc));
+        assert!(check_unhandled_fields(&ifc).has_warnings());
         testing_logger::validate(|captured_logs| {
             captured_logs
                 .iter()
@@ -1336,24 +2879,262 @@ mod tests {
                     .iter()
                     .filter(|l| l.level == Level::Warn)
                     .count(),
-                9
+                12
             );
             assert_eq!(
                 captured_logs
                     .iter()
                     .filter(|l| l.level == Level::Info)
                     .count(),
-                16
+                6
             );
         });
     }
 
     #[test]
-    fn test_check_extra_flags() {
-        assert!(check_extra_flags("A, B,C ", " C,D ,E", "A,B,D,E"));
-        assert!(!check_extra_flags("A, B,C ", " C,D ,E", "A,B,D,E,X"));
-        assert!(check_extra_flags("A,B,C", "C,B,A", ""));
-        assert!(check_extra_flags("A,B,C", "C,B,A,E", "E"));
+    fn test_has_only_mapped_dhcp_flags() {
+        assert!(has_only_mapped_dhcp_flags("group", false));
+        assert!(has_only_mapped_dhcp_flags("required, group", false));
+        assert!(!has_only_mapped_dhcp_flags("group,primary", false));
+        assert!(has_only_mapped_dhcp_flags("group,primary", true));
+        assert!(!has_only_mapped_dhcp_flags("group,unknown", true));
+    }
+
+    #[test]
+    fn test_dhcp_timing() {
+        let mut extra = ExtraSettings::default();
+        add_dhcp_timing("ipv4", "required", 0, &mut extra);
+        add_dhcp_timing("ipv6", "group", 30, &mut extra);
+        assert_eq!(
+            extra.properties,
+            [
+                ("ipv4", "dhcp-timeout", "2147483647"),
+                ("ipv4", "may-fail", "false"),
+                ("ipv6", "dhcp-timeout", "30"),
+                ("ipv6", "required-timeout", "30000"),
+            ]
+            .into_iter()
+            .map(|(setting, key, value)| (
+                (setting.to_string(), key.to_string()),
+                value.to_string()
+            ))
+            .collect::<std::collections::BTreeMap<_, _>>()
+        );
+
+        let mut extra = ExtraSettings::default();
+        add_dhcp_timing("ipv4", "group", default_defer_timeout(), &mut extra);
+        assert!(extra.is_empty());
+
+        let mut extra = ExtraSettings::default();
+        add_dhcp_timing("ipv4", "group", 5_000_000, &mut extra);
+        add_dhcp_timing("ipv6", "group", u32::MAX, &mut extra);
+        assert_eq!(
+            extra.properties,
+            [
+                ("ipv4", "dhcp-timeout", "5000000"),
+                ("ipv4", "required-timeout", "2147483647"),
+                ("ipv6", "dhcp-timeout", "2147483647"),
+                ("ipv6", "required-timeout", "2147483647"),
+            ]
+            .into_iter()
+            .map(|(setting, key, value)| (
+                (setting.to_string(), key.to_string()),
+                value.to_string()
+            ))
+            .collect::<std::collections::BTreeMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn test_dhcp6_mode() {
+        setup_default_migration_settings();
+        let ifc = Interface {
+            name: "eth0".to_string(),
+            ipv6_dhcp: Some(Ipv6Dhcp {
+                mode: "managed,prefix".to_string(),
+                prefix_hint: Some("::/56".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut result = IpConfigResult::default();
+        ifc.add_dhcp6_mode(true, &mut result);
+        assert_eq!(
+            result.extra.properties[&("ipv6".to_string(), "dhcp-pd-hint".to_string())],
+            "::/56"
+        );
+        assert!(!result.findings.has_warnings());
+
+        let mut result = IpConfigResult::default();
+        ifc.add_dhcp6_mode(false, &mut result);
+        assert!(result.extra.is_empty());
+        assert!(result.findings.has_warnings());
+
+        let ipconfig_result = ifc.to_ip_config(&None).unwrap();
+        assert_eq!(ipconfig_result.ip_config.method6, Some(Ipv6Method::Dhcp));
+
+        let ifc = Interface {
+            ipv6_dhcp: Some(Ipv6Dhcp {
+                mode: "info".to_string(),
+                rapid_commit: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let ipconfig_result = ifc.to_ip_config(&None).unwrap();
+        assert_eq!(ipconfig_result.ip_config.method6, Some(Ipv6Method::Auto));
+        assert!(ipconfig_result.extra.is_empty());
+        assert!(check_unhandled_fields(&ifc).has_warnings());
+
+        let ifc = Interface {
+            ipv6_dhcp: Some(Ipv6Dhcp {
+                mode: "prefix,unknown".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(check_unhandled_fields(&ifc).has_warnings());
+    }
+
+    #[test]
+    fn test_dhcp_user_class() {
+        setup_default_migration_settings();
+        let ifc = Interface {
+            name: "eth0".to_string(),
+            ..Default::default()
+        };
+        let user_class = UserClass {
+            format: Some("rfc3004".to_string()),
+            identifiers: vec!["class1".to_string()],
+        };
+        let mut result = IpConfigResult::default();
+        ifc.add_dhcp_user_class("ipv4", &user_class, true, &mut result);
+        ifc.add_dhcp_user_class("ipv6", &user_class, true, &mut result);
+        assert!(!result.findings.has_warnings());
+        assert_eq!(
+            result.extra.properties[&("ipv4".to_string(), "dhcp-user-class".to_string())],
+            "class1"
+        );
+        assert_eq!(
+            result.extra.properties[&("ipv6".to_string(), "dhcp-user-class".to_string())],
+            "class1"
+        );
+
+        // Too old NetworkManager
+        let mut result = IpConfigResult::default();
+        ifc.add_dhcp_user_class("ipv4", &user_class, false, &mut result);
+        assert!(result.extra.is_empty());
+        assert!(result.findings.has_warnings());
+
+        // Sent in a different format
+        let user_class = UserClass {
+            format: Some("string".to_string()),
+            identifiers: vec!["class1".to_string()],
+        };
+        let mut result = IpConfigResult::default();
+        ifc.add_dhcp_user_class("ipv4", &user_class, true, &mut result);
+        assert!(!result.extra.is_empty());
+        assert!(result.findings.has_warnings());
+
+        let user_class = UserClass {
+            format: None,
+            identifiers: vec!["class1".to_string(), "class2".to_string()],
+        };
+        let mut result = IpConfigResult::default();
+        ifc.add_dhcp_user_class("ipv6", &user_class, true, &mut result);
+        assert!(result.extra.is_empty());
+        assert!(result.findings.has_warnings());
+
+        let ifc = Interface {
+            ipv6_dhcp: Some(Ipv6Dhcp {
+                vendor_class: Some(VendorClass6 {
+                    enterprise_number: Some(7075),
+                    data: vec!["vendor".to_string()],
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let findings = check_unhandled_fields(&ifc);
+        assert!(findings
+            .iter()
+            .any(|f| f.code == Diagnostic::DhcpVendorClass));
+        assert!(!findings
+            .iter()
+            .any(|f| f.code == Diagnostic::UnhandledField));
+    }
+
+    #[test]
+    fn test_dhcp_fqdn() {
+        setup_default_migration_settings();
+        let ifc = Interface {
+            name: "eth0".to_string(),
+            ipv4_dhcp: Some(Ipv4Dhcp {
+                hostname: Some("host".to_string()),
+                fqdn: Some(DhcpFqdn {
+                    enabled: FqdnEnabled::Enable,
+                    update: FqdnUpdate::Ptr,
+                    encode: false,
+                    qualify: false,
+                }),
+                vendor_class: Some("vendor".to_string()),
+                ..Default::default()
+            }),
+            ipv6_dhcp: Some(Ipv6Dhcp {
+                hostname: Some("host.example.com".to_string()),
+                fqdn: Some(DhcpFqdn {
+                    enabled: FqdnEnabled::Disable,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let ipconfig_result = ifc.to_ip_config(&None).unwrap();
+        let properties = &ipconfig_result.extra.properties;
+        let property = |setting: &str, key: &str| {
+            properties
+                .get(&(setting.to_string(), key.to_string()))
+                .map(String::as_str)
+        };
+        assert_eq!(property("ipv4", "dhcp-fqdn"), Some("host"));
+        assert_eq!(property("ipv4", "dhcp-hostname-flags"), Some("8"));
+        assert_eq!(
+            property("ipv4", "dhcp-vendor-class-identifier"),
+            Some("vendor")
+        );
+        assert_eq!(property("ipv6", "dhcp-hostname-flags"), None);
+        let dhcp4 = ipconfig_result.ip_config.dhcp4_settings.unwrap();
+        assert_eq!(dhcp4.hostname, None);
+        assert_eq!(dhcp4.send_hostname, Some(true));
+        let dhcp6 = ipconfig_result.ip_config.dhcp6_settings.unwrap();
+        assert_eq!(dhcp6.hostname, None);
+        assert_eq!(dhcp6.send_hostname, Some(false));
+        assert!(!ipconfig_result.findings.has_warnings());
+
+        // A fully qualified hostname is always sent in the DHCPv4 FQDN option
+        let ifc = Interface {
+            name: "eth0".to_string(),
+            ipv4_dhcp: Some(Ipv4Dhcp {
+                hostname: Some("host.example.com".to_string()),
+                fqdn: Some(DhcpFqdn {
+                    enabled: FqdnEnabled::Disable,
+                    update: FqdnUpdate::None,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let ipconfig_result = ifc.to_ip_config(&None).unwrap();
+        assert_eq!(
+            ipconfig_result
+                .extra
+                .properties
+                .get(&("ipv4".to_string(), "dhcp-hostname-flags".to_string())),
+            Some(&"6".to_string())
+        );
+        assert!(ipconfig_result.findings.has_warnings());
     }
 
     #[test]
@@ -1396,7 +3177,7 @@ mod tests {
         });
 
         assert!(connection.ip_config.link_local4 == LinkLocal::Fallback);
-        assert!(!conn_res.has_warnings);
+        assert!(!conn_res.findings.has_warnings());
 
         let ifc = Interface {
             ipv4_auto: Some(Ipv4Auto {
@@ -1409,7 +3190,7 @@ mod tests {
         let connection = &conn_res.connections[0];
         assert!(connection.ip_config.method4 == Some(Ipv4Method::LinkLocal));
         assert!(connection.ip_config.link_local4 == LinkLocal::Auto);
-        assert!(!conn_res.has_warnings);
+        assert!(!conn_res.findings.has_warnings());
 
         let ifc = Interface {
             ipv4_auto: Some(Ipv4Auto {
@@ -1422,7 +3203,7 @@ mod tests {
         let connection = &conn_res.connections[0];
         assert!(connection.ip_config.method4 == Some(Ipv4Method::LinkLocal));
         assert!(connection.ip_config.link_local4 == LinkLocal::Auto);
-        assert!(!conn_res.has_warnings);
+        assert!(!conn_res.findings.has_warnings());
 
         let ifc = Interface {
             ipv4_auto: Some(Ipv4Auto {
@@ -1435,7 +3216,7 @@ mod tests {
         let connection = &conn_res.connections[0];
         assert!(connection.ip_config.method4 == Some(Ipv4Method::LinkLocal));
         assert!(connection.ip_config.link_local4 == LinkLocal::Enabled);
-        assert!(!conn_res.has_warnings);
+        assert!(!conn_res.findings.has_warnings());
     }
 
     #[test]
@@ -1452,7 +3233,7 @@ mod tests {
         };
 
         let ip_result = ifc.to_ip_config(&None).unwrap();
-        assert!(!ip_result.has_warnings);
+        assert!(!ip_result.findings.has_warnings());
 
         let ifc = Interface {
             ipv4_static: Some(Ipv4Static {
@@ -1466,7 +3247,7 @@ mod tests {
         };
 
         let ip_result = ifc.to_ip_config(&None).unwrap();
-        assert!(ip_result.has_warnings);
+        assert!(ip_result.findings.has_warnings());
     }
 
     #[test]
@@ -1499,7 +3280,7 @@ mod tests {
         };
 
         let conn_res = ifc.to_connection(&None).unwrap();
-        assert!(conn_res.has_warnings);
+        assert!(conn_res.findings.has_warnings());
 
         testing_logger::validate(|captured_logs| {
             let warnings: Vec<_> = captured_logs
@@ -0,0 +1,421 @@
+use agama_network::types::Status;
+use agama_network::NetworkState;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions, Permissions};
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Record of all changes a migration did to NetworkManager, used to roll it back.
+///
+/// It contains the previous profiles of modified connections including their secrets, so it's
+/// only readable by root.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Journal {
+    #[serde(default)]
+    pub created: Vec<JournalConnection>,
+    #[serde(default)]
+    pub modified: Vec<ModifiedConnection>,
+    /// sysctl.d drop-ins written for settings NetworkManager doesn't have
+    #[serde(default)]
+    pub sysctl_dropins: Vec<PathBuf>,
+}
+
+#[serde_as]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct JournalConnection {
+    pub id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub uuid: Uuid,
+}
+
+#[serde_as]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModifiedConnection {
+    pub id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub uuid: Uuid,
+    /// `None` for connections NetworkManager doesn't store in a file (e.g. generated ones)
+    pub previous: Option<PreviousProfile>,
+}
+
+/// The stored profile of an existing connection (e.g. `lo`) before wicked2nm changed it.
+///
+/// Restoring the whole file also reverts the settings applied with `nmcli` after the
+/// connection was written.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreviousProfile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Writes a file only readable by the owner, like NetworkManager requires for its profiles.
+fn write_private(path: &Path, contents: &str) -> Result<(), anyhow::Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("Couldn't open {}", path.display()))?;
+    file.set_permissions(Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("Couldn't write {}", path.display()))
+}
+
+impl Journal {
+    /// Compares the state that is about to be written with the current NetworkManager state.
+    ///
+    /// `profiles` are the files NetworkManager stores the current connections in, the ones of
+    /// modified connections are copied into the journal.
+    pub fn from_states(
+        current: &NetworkState,
+        new: &NetworkState,
+        profiles: &HashMap<Uuid, PathBuf>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut journal = Journal::default();
+
+        for connection in &new.connections {
+            let Some(existing) = current
+                .connections
+                .iter()
+                .find(|c| c.uuid == connection.uuid)
+            else {
+                journal.created.push(JournalConnection {
+                    id: connection.id.clone(),
+                    uuid: connection.uuid,
+                });
+                continue;
+            };
+
+            let previous = match profiles.get(&existing.uuid) {
+                Some(path) => Some(PreviousProfile {
+                    path: path.clone(),
+                    contents: fs::read_to_string(path)
+                        .with_context(|| format!("Couldn't read the profile of {}", existing.id))?,
+                }),
+                None => {
+                    log::warn!(
+                        "Connection {} ({}) isn't stored in a file, it can't be rolled back",
+                        existing.id,
+                        existing.uuid
+                    );
+                    None
+                }
+            };
+            journal.modified.push(ModifiedConnection {
+                id: existing.id.clone(),
+                uuid: existing.uuid,
+                previous,
+            });
+        }
+
+        Ok(journal)
+    }
+
+    /// Adds the entries of a later migration, for modified connections the
+    /// oldest previous state is kept.
+    pub fn merge(&mut self, other: Journal) {
+        self.created.extend(other.created);
+        for modified in other.modified {
+            if self.created.iter().any(|c| c.uuid == modified.uuid)
+                || self.modified.iter().any(|m| m.uuid == modified.uuid)
+            {
+                continue;
+            }
+            self.modified.push(modified);
+        }
+        for path in other.sysctl_dropins {
+            if !self.sysctl_dropins.contains(&path) {
+                self.sysctl_dropins.push(path);
+            }
+        }
+    }
+
+    /// Uuids of the connections previous migrations created, empty without a journal.
+    pub fn created_uuids(path: &Path) -> Result<Vec<Uuid>, anyhow::Error> {
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        Ok(Journal::read(path)?
+            .created
+            .into_iter()
+            .map(|c| c.uuid)
+            .collect())
+    }
+
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read journal {}", path.display()))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_private(path, &serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Couldn't write journal {}", path.display()))
+    }
+
+    /// Records the changes into the journal at `path`, merging with a previous journal.
+    pub fn record(self, path: &Path) -> Result<(), anyhow::Error> {
+        let mut journal = if path.exists() {
+            Journal::read(path)?
+        } else {
+            Journal::default()
+        };
+        journal.merge(self);
+        journal.write(path)
+    }
+
+    /// Creates the state that removes the journaled connections on top of the current state.
+    pub fn to_rollback_state(&self, current: &NetworkState) -> Result<NetworkState, anyhow::Error> {
+        let mut state = NetworkState::default();
+
+        for created in &self.created {
+            let Some(connection) = current.connections.iter().find(|c| c.uuid == created.uuid)
+            else {
+                log::info!(
+                    "Connection {} ({}) doesn't exist anymore, skipping",
+                    created.id,
+                    created.uuid
+                );
+                continue;
+            };
+            let mut connection = connection.clone();
+            connection.status = Status::Removed;
+            log::info!("Removing connection {} ({})", created.id, created.uuid);
+            state.add_connection(connection)?;
+        }
+
+        Ok(state)
+    }
+
+    /// Writes back the previous profiles of the modified connections that still exist and
+    /// returns their paths, NetworkManager has to load them again afterwards.
+    pub fn restore_profiles(&self, current: &NetworkState) -> Result<Vec<PathBuf>, anyhow::Error> {
+        let mut paths = vec![];
+
+        for modified in &self.modified {
+            if !current.connections.iter().any(|c| c.uuid == modified.uuid) {
+                log::warn!(
+                    "Modified connection {} ({}) doesn't exist anymore, can't restore it",
+                    modified.id,
+                    modified.uuid
+                );
+                continue;
+            }
+            let Some(previous) = &modified.previous else {
+                log::warn!(
+                    "No previous profile of connection {} ({}) recorded, can't restore it",
+                    modified.id,
+                    modified.uuid
+                );
+                continue;
+            };
+            log::info!("Restoring connection {} ({})", modified.id, modified.uuid);
+            write_private(&previous.path, &previous.contents)?;
+            paths.push(previous.path.clone());
+        }
+
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agama_network::model::Connection;
+
+    fn connection(id: &str) -> Connection {
+        Connection {
+            id: id.to_string(),
+            interface: Some(id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("wicked2nm-test-{}", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_journal_from_states() {
+        let dir = temp_dir();
+        let lo_path = dir.join("lo.nmconnection");
+        write_private(&lo_path, "[connection]\nid=lo\n").unwrap();
+
+        let lo = connection("lo");
+        let eth1 = connection("eth1");
+        let mut current = NetworkState::default();
+        current.add_connection(lo.clone()).unwrap();
+        current.add_connection(eth1.clone()).unwrap();
+
+        let mut new_lo = lo.clone();
+        new_lo.ip_config.nameservers = vec!["192.168.0.10".parse().unwrap()];
+        let eth0 = connection("eth0");
+        let mut new = NetworkState::default();
+        new.add_connection(new_lo).unwrap();
+        new.add_connection(eth0.clone()).unwrap();
+        new.add_connection(eth1.clone()).unwrap();
+
+        let profiles = HashMap::from([(lo.uuid, lo_path.clone())]);
+        let journal = Journal::from_states(&current, &new, &profiles).unwrap();
+        assert_eq!(
+            journal.created,
+            vec![JournalConnection {
+                id: "eth0".to_string(),
+                uuid: eth0.uuid
+            }]
+        );
+        assert_eq!(journal.modified.len(), 2);
+        assert_eq!(journal.modified[0].uuid, lo.uuid);
+        assert_eq!(
+            journal.modified[0].previous,
+            Some(PreviousProfile {
+                path: lo_path,
+                contents: "[connection]\nid=lo\n".to_string(),
+            })
+        );
+        // Not stored in a file
+        assert_eq!(journal.modified[1].uuid, eth1.uuid);
+        assert_eq!(journal.modified[1].previous, None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_journal_rollback() {
+        let dir = temp_dir();
+        let lo_path = dir.join("lo.nmconnection");
+        write_private(
+            &lo_path,
+            "[connection]\nid=lo\n\n[ipv4]\ndns=192.168.0.10;\n",
+        )
+        .unwrap();
+
+        let lo = connection("lo");
+        let eth0 = connection("eth0");
+        let journal = Journal {
+            created: vec![
+                JournalConnection {
+                    id: "eth0".to_string(),
+                    uuid: eth0.uuid,
+                },
+                JournalConnection {
+                    id: "eth1".to_string(),
+                    uuid: Uuid::new_v4(),
+                },
+            ],
+            modified: vec![
+                ModifiedConnection {
+                    id: "lo".to_string(),
+                    uuid: lo.uuid,
+                    previous: Some(PreviousProfile {
+                        path: lo_path.clone(),
+                        contents: "[connection]\nid=lo\n".to_string(),
+                    }),
+                },
+                ModifiedConnection {
+                    id: "eth2".to_string(),
+                    uuid: Uuid::new_v4(),
+                    previous: Some(PreviousProfile {
+                        path: dir.join("eth2.nmconnection"),
+                        contents: "[connection]\nid=eth2\n".to_string(),
+                    }),
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut current = NetworkState::default();
+        current.add_connection(lo.clone()).unwrap();
+        current.add_connection(eth0.clone()).unwrap();
+
+        let state = journal.to_rollback_state(&current).unwrap();
+        assert_eq!(state.connections.len(), 1);
+        assert_eq!(
+            state.get_connection("eth0").unwrap().status,
+            Status::Removed
+        );
+
+        // eth2 doesn't exist anymore
+        let paths = journal.restore_profiles(&current).unwrap();
+        assert_eq!(paths, vec![lo_path.clone()]);
+        assert_eq!(
+            fs::read_to_string(&lo_path).unwrap(),
+            "[connection]\nid=lo\n"
+        );
+        assert_eq!(
+            fs::metadata(&lo_path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert!(!dir.join("eth2.nmconnection").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_journal_merge_and_roundtrip() {
+        let path = temp_dir().join("journal.json");
+        let lo_uuid = Uuid::new_v4();
+
+        Journal {
+            created: vec![JournalConnection {
+                id: "eth0".to_string(),
+                uuid: Uuid::new_v4(),
+            }],
+            modified: vec![ModifiedConnection {
+                id: "lo".to_string(),
+                uuid: lo_uuid,
+                previous: Some(PreviousProfile::default()),
+            }],
+            sysctl_dropins: vec![PathBuf::from("/etc/sysctl.d/70-wicked2nm-eth0.conf")],
+        }
+        .record(&path)
+        .unwrap();
+
+        Journal {
+            created: vec![JournalConnection {
+                id: "eth1".to_string(),
+                uuid: Uuid::new_v4(),
+            }],
+            modified: vec![ModifiedConnection {
+                id: "lo".to_string(),
+                uuid: lo_uuid,
+                previous: Some(PreviousProfile {
+                    path: PathBuf::from("/run/NetworkManager/system-connections/lo.nmconnection"),
+                    contents: "[connection]\nid=lo\n".to_string(),
+                }),
+            }],
+            sysctl_dropins: vec![PathBuf::from("/etc/sysctl.d/70-wicked2nm-eth0.conf")],
+        }
+        .record(&path)
+        .unwrap();
+
+        let journal = Journal::read(&path).unwrap();
+        assert_eq!(journal.created.len(), 2);
+        assert_eq!(journal.modified.len(), 1);
+        // The oldest state of lo has to be kept
+        assert_eq!(
+            journal.modified[0].previous,
+            Some(PreviousProfile::default())
+        );
+        assert_eq!(journal.sysctl_dropins.len(), 1);
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+        assert_eq!(Journal::created_uuids(&path).unwrap().len(), 2);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,772 @@
+use crate::extra::{keyfile_route_options, ExtraSettings};
+use agama_network::model::{self, Connection, ConnectionConfig};
+use agama_network::types::{
+    DhcpClientId, DhcpDuid, DhcpIaid, IpConfig, IpRoute, LinkLocal, MacAddress,
+};
+use anyhow::Context;
+use cidr::IpInet;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::{self, OpenOptions, Permissions};
+use std::io::Write;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+/// In-memory representation of a NetworkManager `.nmconnection` keyfile.
+#[derive(Debug, Default, PartialEq)]
+pub struct Keyfile {
+    sections: Vec<(String, BTreeMap<String, String>)>,
+}
+
+impl Keyfile {
+    fn section_mut(&mut self, section: &str) -> &mut BTreeMap<String, String> {
+        let pos = match self.sections.iter().position(|(name, _)| name == section) {
+            Some(pos) => pos,
+            None => {
+                self.sections.push((section.to_string(), BTreeMap::new()));
+                self.sections.len() - 1
+            }
+        };
+        &mut self.sections[pos].1
+    }
+
+    /// Adds an empty section, NetworkManager requires some of them to determine the type.
+    pub fn add_section(&mut self, section: &str) {
+        self.section_mut(section);
+    }
+
+    pub fn set(&mut self, section: &str, key: &str, value: impl ToString) {
+        self.section_mut(section)
+            .insert(key.to_string(), value.to_string());
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .find(|(name, _)| name == section)
+            .and_then(|(_, entries)| entries.get(key))
+            .map(|v| v.as_str())
+    }
+
+    /// Returns all properties as `section.key` -> value.
+    pub fn properties(&self) -> BTreeMap<String, String> {
+        let mut properties = BTreeMap::new();
+        for (section, entries) in &self.sections {
+            for (key, value) in entries {
+                properties.insert(format!("{section}.{key}"), value.clone());
+            }
+        }
+        properties
+    }
+}
+
+impl fmt::Display for Keyfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (section, entries)) in self.sections.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{section}]")?;
+            for (key, value) in entries {
+                writeln!(f, "{key}={value}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn connection_type(config: &ConnectionConfig) -> &'static str {
+    match config {
+        ConnectionConfig::Ethernet => "ethernet",
+        ConnectionConfig::Dummy => "dummy",
+        ConnectionConfig::Loopback => "loopback",
+        ConnectionConfig::Bond(_) => "bond",
+        ConnectionConfig::Vlan(_) => "vlan",
+        ConnectionConfig::Bridge(_) => "bridge",
+        ConnectionConfig::Wireless(_) => "wifi",
+        ConnectionConfig::Infiniband(_) => "infiniband",
+        ConnectionConfig::Tun(_) => "tun",
+        ConnectionConfig::OvsBridge(_) => "ovs-bridge",
+        ConnectionConfig::OvsPort(_) => "ovs-port",
+        ConnectionConfig::OvsInterface(_) => "ovs-interface",
+    }
+}
+
+fn is_controller(config: &ConnectionConfig) -> bool {
+    matches!(
+        config,
+        ConnectionConfig::Bond(_)
+            | ConnectionConfig::Bridge(_)
+            | ConnectionConfig::OvsBridge(_)
+            | ConnectionConfig::OvsPort(_)
+    )
+}
+
+fn format_inet(inet: &IpInet) -> String {
+    format!("{}/{}", inet.address(), inet.network_length())
+}
+
+fn format_route(route: &IpRoute) -> String {
+    let mut value = format_inet(&route.destination);
+    if let Some(next_hop) = route.next_hop {
+        value.push_str(&format!(",{next_hop}"));
+    } else if route.metric.is_some() {
+        value.push(',');
+    }
+    if let Some(metric) = route.metric {
+        value.push_str(&format!(",{metric}"));
+    }
+    value
+}
+
+fn link_local_to_int(link_local: &LinkLocal) -> u8 {
+    match link_local {
+        LinkLocal::Default => 0,
+        LinkLocal::Auto => 1,
+        LinkLocal::Disabled => 2,
+        LinkLocal::Enabled => 3,
+        LinkLocal::Fallback => 4,
+    }
+}
+
+fn add_ip_config(keyfile: &mut Keyfile, ip_config: &IpConfig) {
+    let mut address4 = 0;
+    let mut address6 = 0;
+    for address in &ip_config.addresses {
+        if address.is_ipv4() {
+            address4 += 1;
+            keyfile.set("ipv4", &format!("address{address4}"), format_inet(address));
+        } else {
+            address6 += 1;
+            keyfile.set("ipv6", &format!("address{address6}"), format_inet(address));
+        }
+    }
+
+    for (i, route) in ip_config.routes4.iter().enumerate() {
+        keyfile.set("ipv4", &format!("route{}", i + 1), format_route(route));
+    }
+    for (i, route) in ip_config.routes6.iter().enumerate() {
+        keyfile.set("ipv6", &format!("route{}", i + 1), format_route(route));
+    }
+
+    let nameservers4: Vec<String> = ip_config
+        .nameservers
+        .iter()
+        .filter(|ns| ns.is_ipv4())
+        .map(|ns| format!("{ns};"))
+        .collect();
+    if !nameservers4.is_empty() {
+        keyfile.set("ipv4", "dns", nameservers4.concat());
+    }
+    let nameservers6: Vec<String> = ip_config
+        .nameservers
+        .iter()
+        .filter(|ns| ns.is_ipv6())
+        .map(|ns| format!("{ns};"))
+        .collect();
+    if !nameservers6.is_empty() {
+        keyfile.set("ipv6", "dns", nameservers6.concat());
+    }
+    if !ip_config.dns_searchlist.is_empty() {
+        let searchlist: Vec<String> = ip_config
+            .dns_searchlist
+            .iter()
+            .map(|s| format!("{s};"))
+            .collect();
+        keyfile.set("ipv4", "dns-search", searchlist.concat());
+    }
+    if let Some(priority) = ip_config.dns_priority4 {
+        keyfile.set("ipv4", "dns-priority", priority);
+    }
+    if let Some(priority) = ip_config.dns_priority6 {
+        keyfile.set("ipv6", "dns-priority", priority);
+    }
+    if ip_config.ignore_auto_dns {
+        keyfile.set("ipv4", "ignore-auto-dns", "true");
+        keyfile.set("ipv6", "ignore-auto-dns", "true");
+    }
+
+    if let Some(dhcp4) = &ip_config.dhcp4_settings {
+        if let Some(send_hostname) = dhcp4.send_hostname {
+            keyfile.set("ipv4", "dhcp-send-hostname", u8::from(send_hostname));
+        }
+        if let Some(hostname) = &dhcp4.hostname {
+            keyfile.set("ipv4", "dhcp-hostname", hostname);
+        }
+        if let Some(send_release) = dhcp4.send_release {
+            keyfile.set("ipv4", "dhcp-send-release", u8::from(send_release));
+        }
+        if dhcp4.client_id != DhcpClientId::default() {
+            keyfile.set("ipv4", "dhcp-client-id", &dhcp4.client_id);
+        }
+        if dhcp4.iaid != DhcpIaid::default() {
+            keyfile.set("ipv4", "dhcp-iaid", &dhcp4.iaid);
+        }
+    }
+    if let Some(dhcp6) = &ip_config.dhcp6_settings {
+        if let Some(send_hostname) = dhcp6.send_hostname {
+            keyfile.set("ipv6", "dhcp-send-hostname", u8::from(send_hostname));
+        }
+        if let Some(hostname) = &dhcp6.hostname {
+            keyfile.set("ipv6", "dhcp-hostname", hostname);
+        }
+        if let Some(send_release) = dhcp6.send_release {
+            keyfile.set("ipv6", "dhcp-send-release", u8::from(send_release));
+        }
+        if dhcp6.iaid != DhcpIaid::default() {
+            keyfile.set("ipv6", "dhcp-iaid", &dhcp6.iaid);
+        }
+        if dhcp6.duid != DhcpDuid::default() {
+            keyfile.set("ipv6", "dhcp-duid", &dhcp6.duid);
+        }
+    }
+
+    if ip_config.link_local4 != LinkLocal::Default {
+        keyfile.set(
+            "ipv4",
+            "link-local",
+            link_local_to_int(&ip_config.link_local4),
+        );
+    }
+    if ip_config.never_default4 == Some(true) {
+        keyfile.set("ipv4", "never-default", "true");
+    }
+    if ip_config.never_default6 == Some(true) {
+        keyfile.set("ipv6", "never-default", "true");
+    }
+    if let Some(privacy) = ip_config.ip6_privacy {
+        keyfile.set("ipv6", "ip6-privacy", privacy);
+    }
+
+    if let Some(method4) = &ip_config.method4 {
+        keyfile.set("ipv4", "method", method4);
+    }
+    if let Some(method6) = &ip_config.method6 {
+        keyfile.set("ipv6", "method", method6);
+    }
+    keyfile.set("ipv6", "addr-gen-mode", "default");
+}
+
+fn add_wireless_config(
+    keyfile: &mut Keyfile,
+    connection: &Connection,
+    config: &model::WirelessConfig,
+) {
+    keyfile.set("wifi", "ssid", String::from_utf8_lossy(&config.ssid.0));
+    keyfile.set(
+        "wifi",
+        "mode",
+        match config.mode {
+            model::WirelessMode::AdHoc => "adhoc",
+            model::WirelessMode::AP => "ap",
+            _ => "infrastructure",
+        },
+    );
+    if config.hidden {
+        keyfile.set("wifi", "hidden", "true");
+    }
+    if let Some(band) = &config.band {
+        keyfile.set("wifi", "band", band);
+    }
+    if config.channel != 0 {
+        keyfile.set("wifi", "channel", config.channel);
+    }
+    if let Some(bssid) = &config.bssid {
+        keyfile.set("wifi", "bssid", bssid);
+    }
+    if !matches!(connection.custom_mac_address, MacAddress::Unset) {
+        keyfile.set("wifi", "cloned-mac-address", &connection.custom_mac_address);
+    }
+
+    keyfile.set("wifi-security", "key-mgmt", &config.security);
+    if let Some(password) = &config.password {
+        keyfile.set("wifi-security", "psk", password);
+    }
+    if let Some(wep) = &config.wep_security {
+        keyfile.set(
+            "wifi-security",
+            "auth-alg",
+            format!("{:?}", wep.auth_alg).to_lowercase(),
+        );
+        keyfile.set("wifi-security", "wep-tx-keyidx", wep.wep_key_index);
+        for (i, key) in wep.keys.iter().enumerate() {
+            keyfile.set("wifi-security", &format!("wep-key{i}"), key);
+        }
+    }
+    if config.pmf != 0 {
+        keyfile.set("wifi-security", "pmf", config.pmf);
+    }
+    let protos: Vec<String> = config
+        .wpa_protocol_versions
+        .iter()
+        .map(|p| format!("{p:?};").to_lowercase())
+        .collect();
+    if !protos.is_empty() {
+        keyfile.set("wifi-security", "proto", protos.concat());
+    }
+    let pairwise: Vec<String> = config
+        .pairwise_algorithms
+        .iter()
+        .map(|p| format!("{p:?};").to_lowercase())
+        .collect();
+    if !pairwise.is_empty() {
+        keyfile.set("wifi-security", "pairwise", pairwise.concat());
+    }
+    let group: Vec<String> = config
+        .group_algorithms
+        .iter()
+        .map(|g| format!("{g:?};").to_lowercase())
+        .collect();
+    if !group.is_empty() {
+        keyfile.set("wifi-security", "group", group.concat());
+    }
+
+    if let Some(ieee_8021x) = &connection.ieee_8021x_config {
+        let eap: Vec<String> = ieee_8021x
+            .eap
+            .iter()
+            .map(|e| format!("{e:?};").to_lowercase())
+            .collect();
+        keyfile.set("802-1x", "eap", eap.concat());
+        if let Some(identity) = &ieee_8021x.identity {
+            keyfile.set("802-1x", "identity", identity);
+        }
+        if let Some(anonymous_identity) = &ieee_8021x.anonymous_identity {
+            keyfile.set("802-1x", "anonymous-identity", anonymous_identity);
+        }
+        if let Some(password) = &ieee_8021x.password {
+            keyfile.set("802-1x", "password", password);
+        }
+        if let Some(phase2_auth) = &ieee_8021x.phase2_auth {
+            keyfile.set(
+                "802-1x",
+                "phase2-auth",
+                format!("{phase2_auth:?}").to_lowercase(),
+            );
+        }
+        if let Some(peap_version) = &ieee_8021x.peap_version {
+            keyfile.set("802-1x", "phase1-peapver", peap_version);
+        }
+        if ieee_8021x.peap_label {
+            keyfile.set("802-1x", "phase1-peaplabel", 1);
+        }
+        if let Some(ca_cert) = &ieee_8021x.ca_cert {
+            keyfile.set("802-1x", "ca-cert", ca_cert);
+        }
+        if let Some(client_cert) = &ieee_8021x.client_cert {
+            keyfile.set("802-1x", "client-cert", client_cert);
+        }
+        if let Some(private_key) = &ieee_8021x.private_key {
+            keyfile.set("802-1x", "private-key", private_key);
+        }
+        if let Some(private_key_password) = &ieee_8021x.private_key_password {
+            keyfile.set("802-1x", "private-key-password", private_key_password);
+        }
+    }
+}
+
+/// Renders a connection into a keyfile, `connections` is needed to resolve the controller.
+pub fn to_keyfile(
+    connection: &Connection,
+    connections: &[Connection],
+) -> Result<Keyfile, anyhow::Error> {
+    let mut keyfile = Keyfile::default();
+    let con_type = connection_type(&connection.config);
+
+    keyfile.set("connection", "id", &connection.id);
+    keyfile.set("connection", "uuid", connection.uuid);
+    keyfile.set("connection", "type", con_type);
+    if let Some(interface) = &connection.interface {
+        keyfile.set("connection", "interface-name", interface);
+    }
+    if !connection.autoconnect {
+        keyfile.set("connection", "autoconnect", "false");
+    }
+    if let Some(zone) = &connection.firewall_zone {
+        keyfile.set("connection", "zone", zone);
+    }
+    if is_controller(&connection.config) {
+        keyfile.set("connection", "autoconnect-slaves", 1);
+    }
+
+    let controller = match connection.controller {
+        Some(controller_uuid) => {
+            let Some(controller) = connections.iter().find(|c| c.uuid == controller_uuid) else {
+                anyhow::bail!(
+                    "Missing controller {controller_uuid} for connection {}",
+                    connection.id
+                );
+            };
+            Some(controller)
+        }
+        None => None,
+    };
+    if let Some(controller) = controller {
+        keyfile.set("connection", "master", controller.uuid);
+        keyfile.set(
+            "connection",
+            "slave-type",
+            connection_type(&controller.config),
+        );
+    }
+
+    match &connection.config {
+        ConnectionConfig::Ethernet
+        | ConnectionConfig::Bond(_)
+        | ConnectionConfig::Vlan(_)
+        | ConnectionConfig::Bridge(_)
+        | ConnectionConfig::Dummy => {
+            keyfile.add_section("ethernet");
+            if !matches!(connection.custom_mac_address, MacAddress::Unset) {
+                keyfile.set(
+                    "ethernet",
+                    "cloned-mac-address",
+                    &connection.custom_mac_address,
+                );
+            }
+            if connection.mtu != 0 {
+                keyfile.set("ethernet", "mtu", connection.mtu);
+            }
+        }
+        ConnectionConfig::Infiniband(_) => {
+            if connection.mtu != 0 {
+                keyfile.set("infiniband", "mtu", connection.mtu);
+            }
+        }
+        _ => {}
+    }
+
+    match &connection.config {
+        ConnectionConfig::Bond(bond) => {
+            keyfile.set("bond", "mode", &bond.mode);
+            for (key, value) in &bond.options.0 {
+                keyfile.set("bond", key, value);
+            }
+        }
+        ConnectionConfig::Vlan(vlan) => {
+            keyfile.set("vlan", "parent", &vlan.parent);
+            keyfile.set("vlan", "id", vlan.id);
+            keyfile.set(
+                "vlan",
+                "protocol",
+                match vlan.protocol {
+                    model::VlanProtocol::IEEE802_1Q => "802.1Q",
+                    model::VlanProtocol::IEEE802_1ad => "802.1ad",
+                },
+            );
+        }
+        ConnectionConfig::Bridge(bridge) => {
+            keyfile.add_section("bridge");
+            if let Some(stp) = bridge.stp {
+                keyfile.set("bridge", "stp", stp);
+            }
+            if let Some(priority) = bridge.priority {
+                keyfile.set("bridge", "priority", priority);
+            }
+            if let Some(forward_delay) = bridge.forward_delay {
+                keyfile.set("bridge", "forward-delay", forward_delay);
+            }
+            if let Some(hello_time) = bridge.hello_time {
+                keyfile.set("bridge", "hello-time", hello_time);
+            }
+            if let Some(max_age) = bridge.max_age {
+                keyfile.set("bridge", "max-age", max_age);
+            }
+            if let Some(ageing_time) = bridge.ageing_time {
+                keyfile.set("bridge", "ageing-time", ageing_time);
+            }
+        }
+        ConnectionConfig::Wireless(wireless) => {
+            add_wireless_config(&mut keyfile, connection, wireless);
+        }
+        ConnectionConfig::Infiniband(infiniband) => {
+            keyfile.set("infiniband", "transport-mode", &infiniband.transport_mode);
+            if let Some(p_key) = infiniband.p_key {
+                keyfile.set("infiniband", "p-key", p_key);
+            }
+            if let Some(parent) = &infiniband.parent {
+                keyfile.set("infiniband", "parent", parent);
+            }
+        }
+        ConnectionConfig::Tun(tun) => {
+            keyfile.set(
+                "tun",
+                "mode",
+                match tun.mode {
+                    model::TunMode::Tun => 1,
+                    model::TunMode::Tap => 2,
+                },
+            );
+            if let Some(owner) = &tun.owner {
+                keyfile.set("tun", "owner", owner);
+            }
+            if let Some(group) = &tun.group {
+                keyfile.set("tun", "group", group);
+            }
+        }
+        ConnectionConfig::OvsBridge(_) => keyfile.add_section("ovs-bridge"),
+        ConnectionConfig::OvsPort(ovs_port) => {
+            keyfile.add_section("ovs-port");
+            if let Some(tag) = ovs_port.tag {
+                keyfile.set("ovs-port", "tag", tag);
+            }
+        }
+        ConnectionConfig::OvsInterface(ovs_interface) => {
+            keyfile.set(
+                "ovs-interface",
+                "type",
+                format!("{:?}", ovs_interface.interface_type).to_lowercase(),
+            );
+        }
+        ConnectionConfig::Ethernet | ConnectionConfig::Dummy | ConnectionConfig::Loopback => {}
+    }
+
+    if let model::PortConfig::Bridge(bridge_port) = &connection.port_config {
+        keyfile.add_section("bridge-port");
+        if let Some(priority) = bridge_port.priority {
+            keyfile.set("bridge-port", "priority", priority);
+        }
+        if let Some(path_cost) = bridge_port.path_cost {
+            keyfile.set("bridge-port", "path-cost", path_cost);
+        }
+    }
+
+    // Bond and bridge ports as well as ovs-bridge/ovs-port don't carry an ip configuration, the
+    // ovs-interface below an ovs-port does
+    let is_port = controller.is_some_and(|c| {
+        matches!(
+            c.config,
+            ConnectionConfig::Bond(_) | ConnectionConfig::Bridge(_)
+        )
+    });
+    if !is_port
+        && !matches!(
+            connection.config,
+            ConnectionConfig::OvsBridge(_) | ConnectionConfig::OvsPort(_)
+        )
+    {
+        add_ip_config(&mut keyfile, &connection.ip_config);
+    }
+
+    Ok(keyfile)
+}
+
+/// Adds the settings the agama model can't express to a rendered connection.
+pub fn add_extra_settings(keyfile: &mut Keyfile, extra: &ExtraSettings) {
+    for ((setting, key), value) in &extra.properties {
+        keyfile.set(setting, key, value);
+    }
+    for (section, attributes) in [
+        ("ipv4", &extra.route_attributes4),
+        ("ipv6", &extra.route_attributes6),
+    ] {
+        for (i, route_attributes) in attributes.iter().enumerate() {
+            if !route_attributes.is_empty() {
+                keyfile.set(
+                    section,
+                    &format!("route{}_options", i + 1),
+                    keyfile_route_options(route_attributes),
+                );
+            }
+        }
+    }
+    for (section, rules) in [
+        ("ipv4", &extra.routing_rules4),
+        ("ipv6", &extra.routing_rules6),
+    ] {
+        for (i, rule) in rules.iter().enumerate() {
+            keyfile.set(section, &format!("routing-rule{}", i + 1), rule);
+        }
+    }
+}
+
+/// Returns the file name of the keyfile for a connection id.
+pub fn keyfile_name(id: &str) -> String {
+    format!("{}.nmconnection", id.replace('/', "_"))
+}
+
+/// Writes all connections as keyfiles into `dir`, returning the paths of the written files.
+pub fn write_keyfiles(
+    connections: &[Connection],
+    extra_settings: &HashMap<String, ExtraSettings>,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, anyhow::Error> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create output directory {}", dir.display()))?;
+
+    let mut paths = vec![];
+    for connection in connections {
+        let mut keyfile = to_keyfile(connection, connections)?;
+        if let Some(extra) = extra_settings.get(&connection.id) {
+            add_extra_settings(&mut keyfile, extra);
+        }
+        let path = dir.join(keyfile_name(&connection.id));
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        // NetworkManager ignores keyfiles which are readable by others,
+        // so also fix permissions of already existing files.
+        file.set_permissions(Permissions::from_mode(0o600))?;
+        file.write_all(keyfile.to_string().as_bytes())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        log::info!("Wrote connection {} to {}", connection.id, path.display());
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agama_network::types::{Ipv4Method, Ipv6Method};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_keyfile_display() {
+        let mut keyfile = Keyfile::default();
+        keyfile.set("connection", "type", "ethernet");
+        keyfile.set("connection", "id", "eth0");
+        keyfile.add_section("ethernet");
+
+        assert_eq!(
+            keyfile.to_string(),
+            "[connection]\nid=eth0\ntype=ethernet\n\n[ethernet]\n"
+        );
+        assert_eq!(keyfile.get("connection", "id"), Some("eth0"));
+        assert_eq!(keyfile.get("ethernet", "mtu"), None);
+    }
+
+    #[test]
+    fn test_static_ethernet_to_keyfile() {
+        let connection = Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            config: ConnectionConfig::Ethernet,
+            mtu: 9000,
+            ip_config: IpConfig {
+                method4: Some(Ipv4Method::Manual),
+                method6: Some(Ipv6Method::Manual),
+                addresses: vec![
+                    IpInet::from_str("192.168.1.5/24").unwrap(),
+                    IpInet::from_str("2001:db8::5/64").unwrap(),
+                ],
+                routes4: vec![IpRoute {
+                    destination: IpInet::from_str("0.0.0.0/0").unwrap(),
+                    next_hop: Some("192.168.1.1".parse().unwrap()),
+                    metric: Some(100),
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let keyfile = to_keyfile(&connection, &[]).unwrap();
+        assert_eq!(keyfile.get("connection", "type"), Some("ethernet"));
+        assert_eq!(keyfile.get("connection", "interface-name"), Some("eth0"));
+        assert_eq!(keyfile.get("connection", "autoconnect"), Some("false"));
+        assert_eq!(keyfile.get("ethernet", "mtu"), Some("9000"));
+        assert_eq!(keyfile.get("ipv4", "address1"), Some("192.168.1.5/24"));
+        assert_eq!(
+            keyfile.get("ipv4", "route1"),
+            Some("0.0.0.0/0,192.168.1.1,100")
+        );
+        assert_eq!(keyfile.get("ipv6", "address1"), Some("2001:db8::5/64"));
+    }
+
+    #[test]
+    fn test_port_to_keyfile() {
+        let bond = Connection {
+            id: "bond0".to_string(),
+            interface: Some("bond0".to_string()),
+            config: ConnectionConfig::Bond(model::BondConfig::default()),
+            ..Default::default()
+        };
+        let port = Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            config: ConnectionConfig::Ethernet,
+            controller: Some(bond.uuid),
+            ..Default::default()
+        };
+        let connections = vec![bond.clone(), port.clone()];
+
+        let keyfile = to_keyfile(&port, &connections).unwrap();
+        assert_eq!(
+            keyfile.get("connection", "master"),
+            Some(bond.uuid.to_string().as_str())
+        );
+        assert_eq!(keyfile.get("connection", "slave-type"), Some("bond"));
+        assert_eq!(keyfile.get("ipv4", "method"), None);
+
+        let keyfile = to_keyfile(&bond, &connections).unwrap();
+        assert_eq!(keyfile.get("connection", "autoconnect-slaves"), Some("1"));
+
+        // Controller missing
+        assert!(to_keyfile(&port, &[]).is_err());
+    }
+
+    #[test]
+    fn test_ovs_interface_to_keyfile() {
+        let ovs_port = Connection {
+            id: "ovs-port-ovsbr0".to_string(),
+            interface: Some("ovs-port-ovsbr0".to_string()),
+            config: ConnectionConfig::OvsPort(model::OvsPortConfig { tag: None }),
+            ..Default::default()
+        };
+        let ovs_interface = Connection {
+            id: "ovsbr0".to_string(),
+            interface: Some("ovsbr0".to_string()),
+            config: ConnectionConfig::OvsInterface(model::OvsInterfaceConfig {
+                interface_type: model::OvsInterfaceType::Internal,
+            }),
+            controller: Some(ovs_port.uuid),
+            ip_config: IpConfig {
+                method4: Some(Ipv4Method::Manual),
+                addresses: vec![IpInet::from_str("192.168.1.5/24").unwrap()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let connections = vec![ovs_port.clone(), ovs_interface.clone()];
+
+        let keyfile = to_keyfile(&ovs_interface, &connections).unwrap();
+        assert_eq!(keyfile.get("connection", "slave-type"), Some("ovs-port"));
+        assert_eq!(keyfile.get("ipv4", "method"), Some("manual"));
+        assert_eq!(keyfile.get("ipv4", "address1"), Some("192.168.1.5/24"));
+
+        let keyfile = to_keyfile(&ovs_port, &connections).unwrap();
+        assert_eq!(keyfile.get("ipv4", "method"), None);
+    }
+
+    #[test]
+    fn test_write_keyfiles() {
+        let dir = std::env::temp_dir().join(format!("wicked2nm-test-{}", uuid::Uuid::new_v4()));
+        let connections = vec![Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            config: ConnectionConfig::Ethernet,
+            ..Default::default()
+        }];
+
+        let mut extra = ExtraSettings::default();
+        extra.set("ethernet", "wake-on-lan", "64");
+        let extra_settings = HashMap::from([("eth0".to_string(), extra)]);
+
+        let paths = write_keyfiles(&connections, &extra_settings, &dir).unwrap();
+        assert_eq!(paths, vec![dir.join("eth0.nmconnection")]);
+        let metadata = fs::metadata(&paths[0]).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        let contents = fs::read_to_string(&paths[0]).unwrap();
+        assert!(contents.starts_with("[connection]\n"));
+        assert!(contents.contains("wake-on-lan=64\n"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+use crate::extra::ExtraSettings;
+use agama_network::model::Connection;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+
+/// A lease file of the NetworkManager internal DHCPv4 client.
+///
+/// The client only reads `ADDRESS` from it and requests that address again on the first start.
+#[derive(Debug, PartialEq)]
+pub struct NmLease {
+    pub path: PathBuf,
+    pub address: Ipv4Addr,
+}
+
+impl NmLease {
+    pub fn contents(&self) -> String {
+        format!(
+            "# This is private data. Do not parse.\nADDRESS={}\n",
+            self.address
+        )
+    }
+
+    pub fn write(&self) -> Result<(), anyhow::Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, self.contents())
+            .with_context(|| format!("Couldn't write DHCP lease {}", self.path.display()))
+    }
+}
+
+/// Collects the imported leases of the connections as `internal-<uuid>-<interface>.lease` in
+/// `dir`.
+pub fn nm_leases(
+    connections: &[Connection],
+    extra_settings: &HashMap<String, ExtraSettings>,
+    dir: &Path,
+) -> Vec<NmLease> {
+    connections
+        .iter()
+        .filter_map(|connection| {
+            let address = extra_settings.get(&connection.id)?.lease_address4?;
+            let interface = connection.interface.as_deref()?;
+            Some(NmLease {
+                path: dir.join(format!("internal-{}-{interface}.lease", connection.uuid)),
+                address,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_nm_leases() {
+        let uuid = Uuid::new_v4();
+        let connections = vec![
+            Connection {
+                id: "eth0".to_string(),
+                uuid,
+                interface: Some("eth0".to_string()),
+                ..Default::default()
+            },
+            Connection {
+                id: "eth1".to_string(),
+                interface: Some("eth1".to_string()),
+                ..Default::default()
+            },
+        ];
+        let extra = ExtraSettings {
+            lease_address4: Some("192.168.122.226".parse().unwrap()),
+            ..Default::default()
+        };
+        let extra_settings = HashMap::from([("eth0".to_string(), extra)]);
+
+        let leases = nm_leases(
+            &connections,
+            &extra_settings,
+            Path::new("/var/lib/NetworkManager"),
+        );
+        assert_eq!(leases.len(), 1);
+        assert_eq!(
+            leases[0].path,
+            PathBuf::from(format!(
+                "/var/lib/NetworkManager/internal-{uuid}-eth0.lease"
+            ))
+        );
+        assert_eq!(
+            leases[0].contents(),
+            "# This is private data. Do not parse.\nADDRESS=192.168.122.226\n"
+        );
+    }
+}
@@ -1,22 +1,42 @@
 mod bond;
 mod bridge;
+mod client_id;
+mod diagnostic;
+mod ethernet;
+mod extra;
+mod filter;
+mod ifcfg;
 mod infiniband;
 mod interface;
+mod journal;
+mod keyfile;
+mod lease;
 mod migrate;
 mod netconfig;
 mod netconfig_dhcp;
 mod ovs;
+mod plan;
 mod reader;
+mod report;
+mod sysctl;
 mod team;
 mod tuntap;
+mod verify;
 mod vlan;
+mod wicked_state;
 mod wireless;
 
 use clap::builder::TypedValueParser;
 use clap::{Args, Parser, Subcommand};
+use diagnostic::Diagnostic;
 use log::*;
-use migrate::{apply_networkstate, to_networkstate};
+use migrate::{
+    apply_networkstate, nm_version, parse_nm_version_arg, plan_networkstate, rollback_networkstate,
+    to_networkstate, write_networkstate, NM_CURRENT_VERSION,
+};
+use plan::PlanAction;
 use reader::read as wicked_read;
+use report::Report;
 use serde::Serialize;
 use simplelog::ConfigBuilder;
 use std::path::PathBuf;
@@ -54,6 +74,24 @@ struct GlobalOpts {
     )]
     pub netconfig_base_dir: PathBuf,
 
+    /// Directory with the state wicked persisted, e.g. the DHCP DUID and IAID.
+    #[arg(
+        long,
+        global = true,
+        default_value = "/var/lib/wicked/",
+        env = "W2NM_WICKED_STATE_DIR"
+    )]
+    pub wicked_state_dir: PathBuf,
+
+    /// Directory with the iproute2 `rt_tables` defining the names of routing tables.
+    #[arg(
+        long,
+        global = true,
+        default_value = "/etc/iproute2/",
+        env = "W2NM_IPROUTE2_DIR"
+    )]
+    pub iproute2_dir: PathBuf,
+
     /// Specify the path to the netconfig config file.
     /// If not set, defaults to $W2NM_NETCONFIG_BASE_DIR/config
     #[arg(long, global = true, env = "W2NM_NETCONFIG_PATH")]
@@ -67,6 +105,65 @@ struct GlobalOpts {
     /// Disable user hints.
     #[arg(long, global = true, env = "W2NM_DISABLE_HINTS")]
     pub disable_hints: bool,
+
+    /// Path of the journal recording the changes done to NetworkManager by `migrate`.
+    #[arg(
+        long,
+        global = true,
+        default_value = "/var/lib/wicked2nm/journal.json",
+        env = "W2NM_JOURNAL"
+    )]
+    pub journal: PathBuf,
+
+    /// Only handle interfaces matching this glob, their ports and parents are included automatically.
+    #[arg(
+        long = "interface",
+        global = true,
+        value_name = "GLOB",
+        value_delimiter = ',',
+        env = "W2NM_INTERFACE"
+    )]
+    pub interfaces: Vec<String>,
+
+    /// Skip interfaces matching this glob.
+    #[arg(
+        long = "exclude-interface",
+        global = true,
+        value_name = "GLOB",
+        value_delimiter = ',',
+        env = "W2NM_EXCLUDE_INTERFACE"
+    )]
+    pub exclude_interfaces: Vec<String>,
+
+    /// Set the ipv6 method of these interfaces to shared, so they get a subnet of the prefix
+    /// delegated via DHCPv6.
+    #[arg(
+        long,
+        global = true,
+        value_name = "INTERFACE",
+        value_delimiter = ',',
+        env = "W2NM_IPV6_SHARED"
+    )]
+    pub ipv6_shared: Vec<String>,
+
+    /// Read ifcfg-*, ifroute-* and routes files directly instead of the output of `wicked show-config`.
+    #[arg(long, global = true, env = "W2NM_IFCFG")]
+    pub ifcfg: bool,
+
+    /// Derive connection uuids from the connection name and type instead of generating random ones.
+    #[arg(long, global = true, env = "W2NM_STABLE_UUIDS")]
+    pub stable_uuids: bool,
+
+    /// Version of the target NetworkManager like `1.44`, deciding which properties are used.
+    /// Defaults to the installed NetworkManager, respectively a current one with --output-dir
+    #[arg(
+        long,
+        global = true,
+        value_name = "X.Y",
+        value_parser = parse_nm_version_arg,
+        env = "W2NM_NM_VERSION"
+    )]
+    pub nm_version: Option<(u32, u32)>,
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -78,15 +175,17 @@ pub enum Commands {
         format: Format,
 
         /// Wicked XML files or directories where the wicked xml configs are located.
-        /// Can also be "-" to read from stdin
-        #[arg(required = true)]
+        /// Can also be "-" to read from stdin. With --ifcfg these are ifcfg files or directories
+        /// and default to the netconfig base dir
+        #[arg(required_unless_present = "ifcfg")]
         paths: Vec<String>,
     },
     /// Migrate wicked state at path
     Migrate {
         /// Wicked XML files or directories where the wicked xml configs are located.
-        /// Can also be "-" to read from stdin
-        #[arg(required = true)]
+        /// Can also be "-" to read from stdin. With --ifcfg these are ifcfg files or directories
+        /// and default to the netconfig base dir
+        #[arg(required_unless_present = "ifcfg")]
         paths: Vec<String>,
 
         /// Continue migration if warnings are encountered
@@ -100,9 +199,89 @@ pub enum Commands {
         /// Activate connections that are marked as autostart immediately
         #[arg(long, global = true, env = "W2NM_ACTIVATE_CONNECTIONS")]
         activate_connections: bool,
+
+        /// Write the connections as NetworkManager keyfiles into this directory
+        /// instead of sending them to NetworkManager (can be run without NetworkManager installed).
+        /// Only for migrate, plan and verify compare with the running system
+        #[arg(long, env = "W2NM_OUTPUT_DIR")]
+        output_dir: Option<PathBuf>,
+
+        /// Print a machine-readable report of the interfaces, connections and findings
+        #[arg(value_enum, long, env = "W2NM_REPORT")]
+        report: Option<ReportFormat>,
+
+        /// Write the report into this file instead of stdout
+        #[arg(long, requires = "report", env = "W2NM_REPORT_FILE")]
+        report_file: Option<PathBuf>,
+
+        /// Update connections created by a previous migration instead of adding duplicates
+        #[arg(long, env = "W2NM_UPDATE_EXISTING")]
+        update_existing: bool,
+
+        /// Directory for the sysctl.d drop-ins of settings NetworkManager has no property for
+        #[arg(long, default_value = "/etc/sysctl.d", env = "W2NM_SYSCTL_DIR")]
+        sysctl_dir: PathBuf,
+
+        /// Import the DHCPv4 leases of wicked, so NetworkManager requests the same addresses
+        #[arg(long, env = "W2NM_IMPORT_LEASES")]
+        import_leases: bool,
+
+        /// Directory for the lease files of the NetworkManager internal DHCP client
+        #[arg(
+            long,
+            default_value = "/var/lib/NetworkManager",
+            env = "W2NM_NM_LEASE_DIR"
+        )]
+        nm_lease_dir: PathBuf,
+
+        /// Don't abort the migration on warnings with this code (e.g. W2NM-TEAM-QUEUE-ID)
+        #[arg(
+            long,
+            value_name = "CODE",
+            value_delimiter = ',',
+            env = "W2NM_ALLOW_WARNING"
+        )]
+        allow_warning: Vec<Diagnostic>,
+
+        /// Always abort the migration on warnings with this code, even with --continue-migration
+        #[arg(
+            long,
+            value_name = "CODE",
+            value_delimiter = ',',
+            env = "W2NM_DENY_WARNING"
+        )]
+        deny_warning: Vec<Diagnostic>,
+    },
+    /// Show how the migration would change the connections NetworkManager currently has
+    Plan {
+        /// Wicked XML files or directories where the wicked xml configs are located.
+        /// Can also be "-" to read from stdin. With --ifcfg these are ifcfg files or directories
+        /// and default to the netconfig base dir
+        #[arg(required_unless_present = "ifcfg")]
+        paths: Vec<String>,
+
+        /// Plan updating connections created by a previous migration instead of adding duplicates
+        #[arg(long, env = "W2NM_UPDATE_EXISTING")]
+        update_existing: bool,
+    },
+    /// Roll back the changes of previous migrations recorded in the journal
+    Rollback,
+    /// Compare the live kernel state with the wicked configuration after a migration
+    Verify {
+        /// Wicked XML files or directories where the wicked xml configs are located.
+        /// Can also be "-" to read from stdin. With --ifcfg these are ifcfg files or directories
+        /// and default to the netconfig base dir
+        #[arg(required_unless_present = "ifcfg")]
+        paths: Vec<String>,
     },
 }
 
+/// Supported report formats
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub enum ReportFormat {
+    Json,
+}
+
 /// Supported output formats
 #[derive(clap::ValueEnum, Clone, Debug)]
 pub enum Format {
@@ -121,9 +300,14 @@ pub enum MigrationError {
     ShowError(anyhow::Error),
     #[error("Migration failed: {0}")]
     MigrationError(anyhow::Error),
+    #[error("Rollback failed: {0}")]
+    RollbackError(anyhow::Error),
+    #[error("Verification failed: {0}")]
+    VerifyError(anyhow::Error),
 }
 
 async fn run_command(cli: Cli) -> Result<(), MigrationError> {
+    let target_nm_version = cli.global_opts.nm_version;
     let mut migration_settings = MigrationSettings {
         continue_migration: true,
         activate_connections: true,
@@ -137,6 +321,20 @@ async fn run_command(cli: Cli) -> Result<(), MigrationError> {
             .netconfig_dhcp_path
             .unwrap_or_else(|| cli.global_opts.netconfig_base_dir.join("dhcp")),
         netconfig_base_dir: cli.global_opts.netconfig_base_dir,
+        wicked_state_dir: cli.global_opts.wicked_state_dir,
+        read_wicked_state: false,
+        iproute2_dir: cli.global_opts.iproute2_dir,
+        journal_path: cli.global_opts.journal,
+        interfaces: cli.global_opts.interfaces,
+        exclude_interfaces: cli.global_opts.exclude_interfaces,
+        ipv6_shared: cli.global_opts.ipv6_shared,
+        ifcfg: cli.global_opts.ifcfg,
+        update_existing: false,
+        stable_uuids: cli.global_opts.stable_uuids,
+        sysctl_dir: PathBuf::from("/etc/sysctl.d"),
+        nm_version: None,
+        import_leases: false,
+        nm_lease_dir: PathBuf::from("/var/lib/NetworkManager"),
     };
 
     match cli.command {
@@ -151,8 +349,30 @@ async fn run_command(cli: Cli) -> Result<(), MigrationError> {
             continue_migration,
             dry_run,
             activate_connections,
+            output_dir,
+            report,
+            report_file,
+            update_existing,
+            sysctl_dir,
+            import_leases,
+            nm_lease_dir,
+            allow_warning,
+            deny_warning,
         } => {
             migration_settings.continue_migration = continue_migration;
+            migration_settings.read_wicked_state = true;
+            migration_settings.update_existing = update_existing;
+            migration_settings.sysctl_dir = sysctl_dir;
+            migration_settings.import_leases = import_leases;
+            migration_settings.nm_lease_dir = nm_lease_dir;
+            // Keyfiles are usually written for another system, don't probe the local NetworkManager
+            migration_settings.nm_version = target_nm_version.or_else(|| {
+                if output_dir.is_some() {
+                    Some(NM_CURRENT_VERSION)
+                } else {
+                    nm_version()
+                }
+            });
             migration_settings.activate_connections = activate_connections;
             MIGRATION_SETTINGS
                 .set(migration_settings)
@@ -167,7 +387,22 @@ async fn run_command(cli: Cli) -> Result<(), MigrationError> {
             let mut network_state_result =
                 to_networkstate(&interfaces_result).map_err(MigrationError::MigrationError)?;
 
-            if !continue_migration && network_state_result.has_warnings {
+            if let Some(ReportFormat::Json) = report {
+                Report::new(&interfaces_result, &network_state_result)
+                    .write_json(report_file.as_deref())
+                    .map_err(MigrationError::MigrationError)?;
+            }
+
+            let blocking = network_state_result.findings.blocking_warnings(
+                continue_migration,
+                &allow_warning,
+                &deny_warning,
+            );
+            if !blocking.is_empty() {
+                let mut codes: Vec<String> = blocking.iter().map(|f| f.code.to_string()).collect();
+                codes.sort();
+                codes.dedup();
+                log::error!("Blocking warnings: {}", codes.join(", "));
                 return Err(MigrationError::Warnings);
             }
 
@@ -178,9 +413,20 @@ async fn run_command(cli: Cli) -> Result<(), MigrationError> {
                 return Ok(());
             }
 
+            if let Some(output_dir) = output_dir {
+                return write_networkstate(
+                    &mut network_state_result.network_state,
+                    interfaces_result.netconfig,
+                    &network_state_result.extra_settings,
+                    &output_dir,
+                )
+                .map_err(MigrationError::MigrationError);
+            }
+
             match apply_networkstate(
                 &mut network_state_result.network_state,
                 interfaces_result.netconfig,
+                &network_state_result.extra_settings,
             )
             .await
             {
@@ -188,6 +434,31 @@ async fn run_command(cli: Cli) -> Result<(), MigrationError> {
                 Err(e) => Err(MigrationError::MigrationError(e)),
             }
         }
+        Commands::Plan {
+            paths,
+            update_existing,
+        } => {
+            migration_settings.read_wicked_state = true;
+            migration_settings.update_existing = update_existing;
+            migration_settings.nm_version = target_nm_version.or_else(nm_version);
+            MIGRATION_SETTINGS
+                .set(migration_settings)
+                .expect("MIGRATION_SETTINGS was set too early");
+            plan_command(paths)
+                .await
+                .map_err(MigrationError::MigrationError)
+        }
+        Commands::Rollback => rollback_networkstate(&migration_settings.journal_path)
+            .await
+            .map_err(MigrationError::RollbackError),
+        Commands::Verify { paths } => {
+            MIGRATION_SETTINGS
+                .set(migration_settings)
+                .expect("MIGRATION_SETTINGS was set too early");
+            let interfaces_result = wicked_read(paths).map_err(MigrationError::VerifyError)?;
+            verify::verify_live_state(&interfaces_result.interfaces)
+                .map_err(MigrationError::VerifyError)
+        }
     }
 }
 
@@ -215,6 +486,31 @@ fn show_command(paths: Vec<String>, format: Format) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn plan_command(paths: Vec<String>) -> anyhow::Result<()> {
+    let interfaces_result = wicked_read(paths)?;
+    let mut network_state_result = to_networkstate(&interfaces_result)?;
+
+    let plans = plan_networkstate(
+        &mut network_state_result.network_state,
+        interfaces_result.netconfig,
+        &network_state_result.extra_settings,
+    )
+    .await?;
+
+    let (mut create, mut update, mut conflict) = (0, 0, 0);
+    for connection_plan in &plans {
+        match connection_plan.action {
+            PlanAction::Create => create += 1,
+            PlanAction::Update => update += 1,
+            PlanAction::Conflict { .. } => conflict += 1,
+            PlanAction::Unchanged => {}
+        }
+        println!("{connection_plan}");
+    }
+    println!("Plan: {create} to create, {update} to update, {conflict} conflicting");
+    Ok(())
+}
+
 /// Represents the result of execution.
 pub enum CliResult {
     /// Successful execution.
@@ -239,6 +535,22 @@ struct MigrationSettings {
     netconfig_base_dir: PathBuf,
     netconfig_path: PathBuf,
     netconfig_dhcp_path: PathBuf,
+    wicked_state_dir: PathBuf,
+    /// Only `migrate` and `plan` read the wicked state, it isn't part of the configuration
+    read_wicked_state: bool,
+    iproute2_dir: PathBuf,
+    journal_path: PathBuf,
+    interfaces: Vec<String>,
+    exclude_interfaces: Vec<String>,
+    ipv6_shared: Vec<String>,
+    ifcfg: bool,
+    update_existing: bool,
+    stable_uuids: bool,
+    sysctl_dir: PathBuf,
+    /// Major and minor version of the installed NetworkManager, if any
+    nm_version: Option<(u32, u32)>,
+    import_leases: bool,
+    nm_lease_dir: PathBuf,
 }
 
 impl Default for MigrationSettings {
@@ -250,6 +562,20 @@ impl Default for MigrationSettings {
             netconfig_base_dir: PathBuf::default(),
             netconfig_path: PathBuf::default(),
             netconfig_dhcp_path: PathBuf::default(),
+            wicked_state_dir: PathBuf::default(),
+            read_wicked_state: false,
+            iproute2_dir: PathBuf::default(),
+            journal_path: PathBuf::default(),
+            interfaces: vec![],
+            exclude_interfaces: vec![],
+            ipv6_shared: vec![],
+            ifcfg: false,
+            update_existing: false,
+            stable_uuids: false,
+            sysctl_dir: PathBuf::default(),
+            nm_version: None,
+            import_leases: false,
+            nm_lease_dir: PathBuf::default(),
         }
     }
 }
@@ -278,7 +604,7 @@ async fn main() -> CliResult {
         match error {
             MigrationError::Warnings => {
                 if !cli.global_opts.disable_hints {
-                    log::info!("Use the `--continue-migration` flag to ignore warnings");
+                    log::info!("Use the `--continue-migration` flag to ignore warnings or `--allow-warning <CODE>` to ignore specific ones");
                 }
                 return CliResult::Warnings;
             }
@@ -316,6 +642,187 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_migrate_output_dir() {
+        let result = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--output-dir",
+            "/tmp/NM-migrated",
+            "some_file.xml",
+        ]);
+        let Commands::Migrate { output_dir, .. } = result.unwrap().command else {
+            panic!("Expected migrate command");
+        };
+        assert_eq!(output_dir, Some(PathBuf::from("/tmp/NM-migrated")));
+    }
+
+    #[test]
+    fn test_nm_version_arg() {
+        let result = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--nm-version",
+            "1.44",
+            "some_file.xml",
+        ])
+        .unwrap();
+        assert_eq!(result.global_opts.nm_version, Some((1, 44)));
+
+        let result = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--nm-version",
+            "new",
+            "some_file.xml",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_migrate_report() {
+        let result = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--report",
+            "json",
+            "--report-file",
+            "/tmp/report.json",
+            "some_file.xml",
+        ]);
+        let Commands::Migrate {
+            report,
+            report_file,
+            ..
+        } = result.unwrap().command
+        else {
+            panic!("Expected migrate command");
+        };
+        assert!(matches!(report, Some(ReportFormat::Json)));
+        assert_eq!(report_file, Some(PathBuf::from("/tmp/report.json")));
+
+        // --report-file is only valid together with --report
+        let result = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--report-file",
+            "/tmp/report.json",
+            "some_file.xml",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_migrate_allow_deny_warning() {
+        let result = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--allow-warning",
+            "W2NM-TEAM-QUEUE-ID,W2NM-TEAM-LACP-KEY",
+            "--deny-warning",
+            "W2NM-UNHANDLED-FIELD",
+            "some_file.xml",
+        ]);
+        let Commands::Migrate {
+            allow_warning,
+            deny_warning,
+            ..
+        } = result.unwrap().command
+        else {
+            panic!("Expected migrate command");
+        };
+        assert_eq!(
+            allow_warning,
+            vec![Diagnostic::TeamQueueId, Diagnostic::TeamLacpKey]
+        );
+        assert_eq!(deny_warning, vec![Diagnostic::UnhandledField]);
+
+        let result = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--allow-warning",
+            "W2NM-DOES-NOT-EXIST",
+            "some_file.xml",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_update_existing_args() {
+        let cli =
+            Cli::try_parse_from(&["wicked2nm", "migrate", "--update-existing", "some_file.xml"]);
+        let Commands::Migrate {
+            update_existing, ..
+        } = cli.unwrap().command
+        else {
+            panic!("Expected migrate command");
+        };
+        assert!(update_existing);
+
+        let cli = Cli::try_parse_from(&["wicked2nm", "plan", "some_file.xml"]);
+        let Commands::Plan {
+            update_existing, ..
+        } = cli.unwrap().command
+        else {
+            panic!("Expected plan command");
+        };
+        assert!(!update_existing);
+    }
+
+    #[test]
+    fn test_interface_filter_args() {
+        let cli = Cli::try_parse_from(&[
+            "wicked2nm",
+            "migrate",
+            "--interface",
+            "bond*,eth0",
+            "--exclude-interface",
+            "eth1",
+            "some_file.xml",
+        ])
+        .unwrap();
+        assert_eq!(cli.global_opts.interfaces, vec!["bond*", "eth0"]);
+        assert_eq!(cli.global_opts.exclude_interfaces, vec!["eth1"]);
+    }
+
+    #[test]
+    fn test_ifcfg_without_paths() {
+        let cli = Cli::try_parse_from(&["wicked2nm", "migrate", "--ifcfg"]).unwrap();
+        assert!(cli.global_opts.ifcfg);
+        let Commands::Migrate { paths, .. } = cli.command else {
+            panic!("Expected migrate command");
+        };
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn test_rollback_journal() {
+        let result = Cli::try_parse_from(&["wicked2nm", "rollback"]).unwrap();
+        assert!(matches!(result.command, Commands::Rollback));
+        assert_eq!(
+            result.global_opts.journal,
+            PathBuf::from("/var/lib/wicked2nm/journal.json")
+        );
+
+        let result =
+            Cli::try_parse_from(&["wicked2nm", "rollback", "--journal", "/tmp/journal.json"])
+                .unwrap();
+        assert_eq!(
+            result.global_opts.journal,
+            PathBuf::from("/tmp/journal.json")
+        );
+    }
+
+    #[test]
+    fn test_plan_without_paths_fails() {
+        let result = Cli::try_parse_from(&["wicked2nm", "plan"]);
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
     #[test]
     fn test_show_with_paths_passes() {
         let result = Cli::try_parse_from(&["wicked2nm", "show", "some_file.xml"]);
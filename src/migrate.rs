@@ -1,15 +1,26 @@
+use crate::diagnostic::Diagnostic;
+use crate::extra::ExtraSettings;
 use crate::interface::{ConnectionResult, Link, LinkPort, LinkPortType};
+use crate::journal::Journal;
+use crate::keyfile::{connection_type, write_keyfiles};
+use crate::lease::nm_leases;
 use crate::netconfig::{apply_dns_policy, Netconfig};
+use crate::plan::{plan, ConnectionPlan};
 use crate::reader::InterfacesResult;
+use crate::report::Findings;
+use crate::sysctl::{sysctl_dropins, SysctlDropin};
 use crate::MIGRATION_SETTINGS;
 use agama_network::model::{Connection, ConnectionConfig, MatchConfig, StateConfig};
 use agama_network::types::{IpConfig, Ipv4Method, Ipv6Method};
 use agama_network::{model, Adapter, NetworkManagerAdapter, NetworkState};
+use anyhow::Context;
 use cidr::IpInet;
 use nix::ifaddrs::getifaddrs;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -71,11 +82,14 @@ fn update_parent_connection(
             .iter()
             .find(|c| c.interface == parent.master)
         else {
-            log::warn!(
-                "Missing parent connection with interface {} for port {port_uuid}",
-                parent.clone().master.unwrap()
+            cresult.findings.warn(
+                Diagnostic::MissingParent,
+                Some("link.master"),
+                format!(
+                    "Missing parent connection with interface {} for port {port_uuid}",
+                    parent.clone().master.unwrap()
+                ),
             );
-            cresult.has_warnings = true;
             continue;
         };
 
@@ -121,8 +135,8 @@ struct TeamPortOptions {
 fn apply_team_port_options_to_bond(
     connections: &mut [Connection],
     original_parents: &HashMap<Uuid, Link>,
-) -> bool {
-    let mut has_warnings = false;
+) -> Findings {
+    let mut findings = Findings::default();
 
     // Build a map of bond UUID -> list of team port options
     let mut bond_ports: HashMap<Uuid, Vec<TeamPortOptions>> = HashMap::new();
@@ -158,6 +172,10 @@ fn apply_team_port_options_to_bond(
 
     // Now update bond options based on collected port info
     for connection in connections.iter_mut() {
+        let bond_name = connection
+            .interface
+            .clone()
+            .unwrap_or_else(|| connection.id.clone());
         let ConnectionConfig::Bond(bond_config) = &mut connection.config else {
             continue;
         };
@@ -174,11 +192,14 @@ fn apply_team_port_options_to_bond(
         // warn about sticky ports
         if ports_with_prio.is_empty() {
             for sticky_port in sticky_ports {
-                log::warn!(
-                    "Team port '{}' is marked as sticky. Bond requires a primary port (with prio set) to use sticky behavior.",
-                    sticky_port.name
+                findings.warn_for(
+                    &bond_name, Diagnostic::TeamSticky,
+                    Some("link.port.sticky"),
+                    format!(
+                        "Team port '{}' is marked as sticky. Bond requires a primary port (with prio set) to use sticky behavior.",
+                        sticky_port.name
+                    ),
                 );
-                has_warnings = true;
             }
             continue;
         }
@@ -200,12 +221,15 @@ fn apply_team_port_options_to_bond(
                 .iter()
                 .map(|p| p.name.as_str())
                 .collect();
-            log::warn!(
-                "Team has multiple ports {:?} with the same highest prio={}. Bond requires a single primary port, not setting primary.",
-                names,
-                max_prio
+            findings.warn_for(
+                &bond_name, Diagnostic::TeamPrioAmbiguous,
+                Some("link.port.prio"),
+                format!(
+                    "Team has multiple ports {:?} with the same highest prio={}. Bond requires a single primary port, not setting primary.",
+                    names,
+                    max_prio
+                ),
             );
-            has_warnings = true;
             continue;
         }
 
@@ -227,31 +251,45 @@ fn apply_team_port_options_to_bond(
             ports_with_prio.iter().map(|p| p.prio.unwrap()).collect();
 
         if unique_prios.len() > 2 {
-            log::warn!(
-                "Team has {} different priority levels, but bond only supports primary vs backup (2 levels). Port '{}' with prio={} set as bond primary.",
-                unique_prios.len(),
-                port.name,
-                port.prio.unwrap()
+            findings.warn_for(
+                &bond_name, Diagnostic::TeamPrioLevels,
+                Some("link.port.prio"),
+                format!(
+                    "Team has {} different priority levels, but bond only supports primary vs backup (2 levels). Port '{}' with prio={} set as bond primary.",
+                    unique_prios.len(),
+                    port.name,
+                    port.prio.unwrap()
+                ),
             );
-            has_warnings = true;
         } else {
-            log::info!(
-                "Team port '{}' with highest prio={} mapped to bond primary",
-                port.name,
-                port.prio.unwrap()
+            findings.info_for(
+                &bond_name,
+                Diagnostic::TeamPrioPrimary,
+                Some("link.port.prio"),
+                format!(
+                    "Team port '{}' with highest prio={} mapped to bond primary",
+                    port.name,
+                    port.prio.unwrap()
+                ),
             );
         }
 
         // Warn if other ports are sticky (bond doesn't support per-port sticky)
         for sticky_port in &sticky_ports {
             if sticky_port.name != port.name {
-                log::warn!("Team port '{}' is marked as sticky. Bonding only allows the primary port to be sticky.", sticky_port.name);
-                has_warnings = true;
+                findings.warn_for(
+                    &bond_name, Diagnostic::TeamSticky,
+                    Some("link.port.sticky"),
+                    format!(
+                        "Team port '{}' is marked as sticky. Bonding only allows the primary port to be sticky.",
+                        sticky_port.name
+                    ),
+                );
             }
         }
     }
 
-    has_warnings
+    findings
 }
 
 fn create_lo_connection() -> Connection {
@@ -276,7 +314,11 @@ fn create_lo_connection() -> Connection {
 #[derive(Default)]
 pub struct NetworkStateResult {
     pub network_state: NetworkState,
-    pub has_warnings: bool,
+    /// Uuids of the connections created for each wicked interface
+    pub interface_connections: HashMap<String, Vec<Uuid>>,
+    /// Settings not covered by the agama model, by connection id
+    pub extra_settings: HashMap<String, ExtraSettings>,
+    pub findings: Findings,
 }
 
 pub fn to_networkstate(
@@ -284,14 +326,47 @@ pub fn to_networkstate(
 ) -> Result<NetworkStateResult, anyhow::Error> {
     let settings = MIGRATION_SETTINGS.get().unwrap();
     let mut parents: HashMap<Uuid, Link> = HashMap::new();
+    let mut interface_connections: HashMap<String, Vec<Uuid>> = HashMap::new();
     let mut connection_result: ConnectionResult = ConnectionResult {
-        has_warnings: interface_result.has_warnings,
+        findings: interface_result.findings.clone(),
         ..Default::default()
     };
 
     for interface in &interface_result.interfaces {
-        let ifc_connection_result = interface.to_connection(&interface_result.netconfig_dhcp)?;
-        connection_result.has_warnings |= ifc_connection_result.has_warnings;
+        let mut ifc_connection_result =
+            interface.to_connection(&interface_result.netconfig_dhcp)?;
+        if let Some(wicked_state) = &interface_result.wicked_state {
+            for connection in &ifc_connection_result.connections {
+                if connection.interface.as_ref() != Some(&interface.name) {
+                    continue;
+                }
+                let mut extra = ifc_connection_result
+                    .extra_settings
+                    .remove(&connection.id)
+                    .unwrap_or_default();
+                wicked_state.add_dhcp_identity(interface, &mut extra);
+                wicked_state.add_lease(interface, &mut extra);
+                if !extra.is_empty() {
+                    ifc_connection_result
+                        .extra_settings
+                        .insert(connection.id.clone(), extra);
+                }
+            }
+        }
+        connection_result
+            .findings
+            .append(ifc_connection_result.findings);
+        connection_result
+            .extra_settings
+            .extend(ifc_connection_result.extra_settings);
+        interface_connections.insert(
+            interface.name.clone(),
+            ifc_connection_result
+                .connections
+                .iter()
+                .map(|c| c.uuid)
+                .collect(),
+        );
 
         for connection in ifc_connection_result.connections {
             if connection.controller.is_none() {
@@ -363,8 +438,12 @@ pub fn to_networkstate(
     }
 
     // Apply team port options (prio, sticky) to bond configuration
-    connection_result.has_warnings |=
-        apply_team_port_options_to_bond(&mut connection_result.connections, &original_parents);
+    connection_result
+        .findings
+        .append(apply_team_port_options_to_bond(
+            &mut connection_result.connections,
+            &original_parents,
+        ));
 
     if settings.activate_connections {
         let system_interfaces = list_system_interfaces()?;
@@ -397,7 +476,9 @@ pub fn to_networkstate(
     }
 
     let mut state_result = NetworkStateResult {
-        has_warnings: connection_result.has_warnings,
+        interface_connections,
+        extra_settings: connection_result.extra_settings,
+        findings: connection_result.findings,
         ..Default::default()
     };
 
@@ -410,41 +491,351 @@ pub fn to_networkstate(
     Ok(state_result)
 }
 
+/// Adds the loopback connection holding the static DNS settings and applies the DNS policy.
+fn apply_netconfig(
+    state: &mut NetworkState,
+    netconfig: &Netconfig,
+    mut loopback: Connection,
+) -> Result<(), anyhow::Error> {
+    loopback.ip_config.nameservers = netconfig.static_dns_servers.clone();
+
+    if let Some(static_dns_searchlist) = &netconfig.static_dns_searchlist {
+        loopback.ip_config.dns_searchlist = static_dns_searchlist.clone();
+    }
+
+    state.add_connection(loopback)?;
+
+    apply_dns_policy(netconfig, state)?;
+
+    // When a connection didn't get a dns priority it means it wasn't matched by the netconfig policy,
+    // so ignore-auto-dns should be set to true.
+    for con in state.connections.iter_mut() {
+        if con.id != "lo"
+            && con.ip_config.dns_priority4.is_none()
+            && con.ip_config.dns_priority6.is_none()
+        {
+            con.ip_config.ignore_auto_dns = true;
+        }
+    }
+    Ok(())
+}
+
 pub async fn apply_networkstate(
     state: &mut NetworkState,
     netconfig: Option<Netconfig>,
+    extra_settings: &HashMap<String, ExtraSettings>,
+) -> Result<(), anyhow::Error> {
+    let settings = MIGRATION_SETTINGS.get().unwrap();
+    let nm = NetworkManagerAdapter::from_system().await?;
+    let current_state = nm.read(StateConfig::default()).await?;
+
+    if let Some(netconfig) = netconfig {
+        apply_netconfig(state, &netconfig, current_loopback(&current_state))?;
+    }
+
+    if settings.update_existing {
+        reuse_existing_uuids(
+            state,
+            &current_state,
+            &Journal::created_uuids(&settings.journal_path)?,
+        );
+    }
+
+    let dropins = sysctl_dropins(&state.connections, extra_settings, &settings.sysctl_dir);
+
+    // Record the journal before writing so a partially applied migration can be rolled back too
+    let mut journal = Journal::from_states(&current_state, state, &nm_profile_paths()?)?;
+    journal.sysctl_dropins = dropins.iter().map(|d| d.path.clone()).collect();
+    journal.record(&settings.journal_path)?;
+    log::info!(
+        "Recorded migration journal at {}",
+        settings.journal_path.display()
+    );
+
+    nm.write(state).await?;
+    // The connections are committed already, undo them instead of leaving a half applied migration
+    if let Err(e) = finish_networkstate(state, extra_settings, &dropins) {
+        log::error!("Migration failed, rolling back: {e}");
+        if let Err(rollback_error) = rollback_networkstate(&settings.journal_path).await {
+            anyhow::bail!(
+                "{e}. Rolling back failed too: {rollback_error}, the changes are recorded in {}",
+                settings.journal_path.display()
+            );
+        }
+        return Err(e.context("The migration was rolled back"));
+    }
+    Ok(())
+}
+
+/// Applies everything that follows writing the connections: the extra settings, sysctl drop-ins
+/// and DHCP leases.
+fn finish_networkstate(
+    state: &NetworkState,
+    extra_settings: &HashMap<String, ExtraSettings>,
+    dropins: &[SysctlDropin],
 ) -> Result<(), anyhow::Error> {
+    let settings = MIGRATION_SETTINGS.get().unwrap();
+    apply_extra_settings(state, extra_settings)?;
+    for dropin in dropins {
+        log::info!("Writing sysctl drop-in {}", dropin.path.display());
+        dropin.write()?;
+        dropin.apply();
+    }
+    // Written after the connections so the lease files use the final UUIDs
+    for lease in nm_leases(&state.connections, extra_settings, &settings.nm_lease_dir) {
+        log::info!("Writing DHCP lease {}", lease.path.display());
+        lease.write()?;
+    }
+    Ok(())
+}
+
+/// Applies the settings the agama model can't express with `nmcli`, active connections are
+/// reactivated to pick them up.
+fn apply_extra_settings(
+    state: &NetworkState,
+    extra_settings: &HashMap<String, ExtraSettings>,
+) -> Result<(), anyhow::Error> {
+    for connection in &state.connections {
+        let Some(extra) = extra_settings.get(&connection.id) else {
+            continue;
+        };
+        let extra_args = extra.nmcli_args(&connection.ip_config);
+        if extra_args.is_empty() {
+            continue;
+        }
+        let uuid = connection.uuid.to_string();
+        let mut args = vec!["connection".to_string(), "modify".to_string(), uuid.clone()];
+        args.extend(extra_args);
+        log::debug!("Running nmcli {}", args.join(" "));
+        run_nmcli(&args)
+            .with_context(|| format!("Failed to apply the extra settings of {}", connection.id))?;
+
+        // The settings are stored, they are applied at the latest when the connection goes up again
+        if matches!(connection.status, agama_network::types::Status::Up) {
+            if let Err(e) = run_nmcli(&["connection".to_string(), "up".to_string(), uuid]) {
+                log::warn!("Couldn't reactivate {}: {e}", connection.id);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_nmcli(args: &[String]) -> Result<String, anyhow::Error> {
+    let output = Command::new("nmcli")
+        .args(args)
+        .output()
+        .context("Failed to run nmcli")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "nmcli {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The files NetworkManager stores its connections in, by uuid.
+fn nm_profile_paths() -> Result<HashMap<Uuid, PathBuf>, anyhow::Error> {
+    let output = run_nmcli(&[
+        "--terse".to_string(),
+        "--fields".to_string(),
+        "UUID,FILENAME".to_string(),
+        "connection".to_string(),
+        "show".to_string(),
+    ])?;
+    Ok(parse_nm_profile_paths(&output))
+}
+
+fn parse_nm_profile_paths(output: &str) -> HashMap<Uuid, PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (uuid, filename) = line.split_once(':')?;
+            if filename.is_empty() {
+                return None;
+            }
+            // --terse escapes colons and backslashes in values
+            let filename = filename.replace("\\:", ":").replace("\\\\", "\\");
+            Some((uuid.parse().ok()?, PathBuf::from(filename)))
+        })
+        .collect()
+}
+
+/// NetworkManager version assumed for keyfiles written with `--output-dir`, it supports all
+/// properties the migration gates on the version.
+pub const NM_CURRENT_VERSION: (u32, u32) = (1, 54);
+
+/// Version of the installed NetworkManager, used to decide which properties it supports.
+pub fn nm_version() -> Option<(u32, u32)> {
+    let output = Command::new("NetworkManager")
+        .arg("--version")
+        .output()
+        .ok()?;
+    parse_nm_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_nm_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim().split(['.', '-']);
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// Parses the `--nm-version` argument like `1.44`.
+pub fn parse_nm_version_arg(version: &str) -> Result<(u32, u32), String> {
+    parse_nm_version(version).ok_or_else(|| format!("Invalid NetworkManager version {version}"))
+}
+
+/// Compares the network state with the current NetworkManager state without writing anything.
+pub async fn plan_networkstate(
+    state: &mut NetworkState,
+    netconfig: Option<Netconfig>,
+    extra_settings: &HashMap<String, ExtraSettings>,
+) -> Result<Vec<ConnectionPlan>, anyhow::Error> {
+    let settings = MIGRATION_SETTINGS.get().unwrap();
     let nm = NetworkManagerAdapter::from_system().await?;
+    let current_state = nm.read(StateConfig::default()).await?;
 
     if let Some(netconfig) = netconfig {
-        let current_state = nm.read(StateConfig::default()).await?;
-        let mut loopback = match current_state.get_connection("lo") {
-            Some(lo) => lo.clone(),
-            None => create_lo_connection(),
+        apply_netconfig(state, &netconfig, current_loopback(&current_state))?;
+    }
+
+    if settings.update_existing {
+        reuse_existing_uuids(
+            state,
+            &current_state,
+            &Journal::created_uuids(&settings.journal_path)?,
+        );
+    }
+
+    plan(&current_state, state, extra_settings)
+}
+
+/// Gives connections that already exist in NetworkManager from a previous migration their
+/// existing uuid, so they get updated instead of duplicated.
+///
+/// Only connections wicked2nm created are taken over, i.e. the ones recorded as created in the
+/// journal (`created`) or with the same `--stable-uuids` uuid. A journaled connection matches
+/// when id, interface and type are the same, which is how wicked2nm names the connections it
+/// creates.
+fn reuse_existing_uuids(state: &mut NetworkState, current: &NetworkState, created: &[Uuid]) {
+    let mut uuids: HashMap<Uuid, Uuid> = HashMap::new();
+    for connection in &state.connections {
+        if current
+            .connections
+            .iter()
+            .any(|c| c.uuid == connection.uuid)
+        {
+            continue;
+        }
+        let Some(existing) = current.connections.iter().find(|c| {
+            c.id == connection.id
+                && c.interface == connection.interface
+                && connection_type(&c.config) == connection_type(&connection.config)
+        }) else {
+            continue;
         };
-        loopback.ip_config.nameservers = netconfig.static_dns_servers.clone();
+        if !created.contains(&existing.uuid) {
+            log::warn!(
+                "Not updating connection {} ({}), it wasn't created by wicked2nm",
+                existing.id,
+                existing.uuid
+            );
+            continue;
+        }
+        log::info!(
+            "Updating existing connection {} ({})",
+            existing.id,
+            existing.uuid
+        );
+        uuids.insert(connection.uuid, existing.uuid);
+    }
 
-        if let Some(static_dns_searchlist) = &netconfig.static_dns_searchlist {
-            loopback.ip_config.dns_searchlist = static_dns_searchlist.clone();
+    for connection in state.connections.iter_mut() {
+        if let Some(uuid) = uuids.get(&connection.uuid) {
+            connection.uuid = *uuid;
         }
+        if let Some(uuid) = connection.controller.and_then(|c| uuids.get(&c)) {
+            connection.controller = Some(*uuid);
+        }
+    }
+}
 
-        state.add_connection(loopback)?;
+fn current_loopback(current_state: &NetworkState) -> Connection {
+    match current_state.get_connection("lo") {
+        Some(lo) => lo.clone(),
+        None => create_lo_connection(),
+    }
+}
+
+/// Reverts the changes recorded in the journal at `journal_path`.
+pub async fn rollback_networkstate(journal_path: &Path) -> Result<(), anyhow::Error> {
+    let journal = Journal::read(journal_path)?;
+    let nm = NetworkManagerAdapter::from_system().await?;
+    let current_state = nm.read(StateConfig::default()).await?;
 
-        apply_dns_policy(&netconfig, state)?;
+    let state = journal.to_rollback_state(&current_state)?;
+    nm.write(&state).await?;
 
-        // When a connection didn't get a dns priority it means it wasn't matched by the netconfig policy,
-        // so ignore-auto-dns should be set to true.
-        for con in state.connections.iter_mut() {
-            if con.id != "lo"
-                && con.ip_config.dns_priority4.is_none()
-                && con.ip_config.dns_priority6.is_none()
-            {
-                con.ip_config.ignore_auto_dns = true;
+    let paths = journal.restore_profiles(&current_state)?;
+    if !paths.is_empty() {
+        let mut args = vec!["connection".to_string(), "load".to_string()];
+        args.extend(paths.iter().map(|p| p.display().to_string()));
+        run_nmcli(&args)?;
+    }
+    // Loading doesn't apply the restored profiles to active connections
+    for modified in &journal.modified {
+        let active = current_state.connections.iter().any(|c| {
+            c.uuid == modified.uuid && matches!(c.status, agama_network::types::Status::Up)
+        });
+        if active && modified.previous.is_some() {
+            run_nmcli(&[
+                "connection".to_string(),
+                "up".to_string(),
+                modified.uuid.to_string(),
+            ])?;
+        }
+    }
+
+    for path in &journal.sysctl_dropins {
+        log::info!("Removing sysctl drop-in {}", path.display());
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                anyhow::bail!("Couldn't remove {}: {e}", path.display())
             }
+            _ => {}
         }
     }
 
-    nm.write(state).await?;
+    std::fs::remove_file(journal_path)?;
+    Ok(())
+}
+
+/// Writes the network state as keyfiles into `output_dir` instead of sending it to NetworkManager.
+///
+/// Nothing is written outside of `output_dir`, the sysctl drop-ins go into its `sysctl.d` and
+/// the DHCP leases into its `leases` subdirectory.
+pub fn write_networkstate(
+    state: &mut NetworkState,
+    netconfig: Option<Netconfig>,
+    extra_settings: &HashMap<String, ExtraSettings>,
+    output_dir: &Path,
+) -> Result<(), anyhow::Error> {
+    if let Some(netconfig) = netconfig {
+        apply_netconfig(state, &netconfig, create_lo_connection())?;
+    }
+
+    write_keyfiles(&state.connections, extra_settings, output_dir)?;
+
+    let sysctl_dir = output_dir.join("sysctl.d");
+    for dropin in sysctl_dropins(&state.connections, extra_settings, &sysctl_dir) {
+        log::info!("Writing sysctl drop-in {}", dropin.path.display());
+        dropin.write()?;
+    }
+    let lease_dir = output_dir.join("leases");
+    for lease in nm_leases(&state.connections, extra_settings, &lease_dir) {
+        log::info!("Writing DHCP lease {}", lease.path.display());
+        lease.write()?;
+    }
     Ok(())
 }
 
@@ -530,7 +921,8 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
@@ -609,11 +1001,12 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
-        assert!(!result.has_warnings); // No warnings - perfect mapping
+        assert!(!result.findings.has_warnings()); // No warnings - perfect mapping
 
         let team0 = result
             .network_state
@@ -709,11 +1102,12 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
-        assert!(result.has_warnings);
+        assert!(result.findings.has_warnings());
 
         let team0 = result
             .network_state
@@ -799,11 +1193,12 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
-        assert!(result.has_warnings);
+        assert!(result.findings.has_warnings());
 
         let team0 = result
             .network_state
@@ -871,11 +1266,12 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
-        assert!(result.has_warnings);
+        assert!(result.findings.has_warnings());
 
         let team0 = result
             .network_state
@@ -961,11 +1357,12 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
-        assert!(result.has_warnings);
+        assert!(result.findings.has_warnings());
 
         let team0 = result
             .network_state
@@ -1075,11 +1472,12 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
-        assert!(!result.has_warnings); // Should map cleanly - only 2 priority levels (100 and 50)
+        assert!(!result.findings.has_warnings()); // Should map cleanly - only 2 priority levels (100 and 50)
 
         let team0 = result
             .network_state
@@ -1186,13 +1584,14 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
 
         // Should not have warnings - single prio is unambiguous
-        assert!(!result.has_warnings);
+        assert!(!result.findings.has_warnings());
 
         // Verify team was converted to bond
         let team0 = result
@@ -1376,7 +1775,8 @@ mod tests {
             interfaces,
             netconfig: None,
             netconfig_dhcp: None,
-            has_warnings: false,
+            wicked_state: None,
+            findings: Findings::default(),
         };
 
         let result = to_networkstate(&interfaces_result).unwrap();
@@ -1455,4 +1855,162 @@ mod tests {
             "eth4 should exist"
         );
     }
+
+    #[test]
+    fn test_reuse_existing_uuids() {
+        let existing_bond = Connection {
+            id: "bond0".to_string(),
+            interface: Some("bond0".to_string()),
+            config: ConnectionConfig::Bond(Default::default()),
+            ..Default::default()
+        };
+        // Same name but a different type isn't reused
+        let existing_eth1 = Connection {
+            id: "eth1".to_string(),
+            interface: Some("eth1".to_string()),
+            config: ConnectionConfig::Dummy,
+            ..Default::default()
+        };
+        // Same name and type but not created by wicked2nm isn't reused
+        let existing_eth2 = Connection {
+            id: "eth2".to_string(),
+            interface: Some("eth2".to_string()),
+            ..Default::default()
+        };
+        let mut current = NetworkState::default();
+        current.add_connection(existing_bond.clone()).unwrap();
+        current.add_connection(existing_eth1.clone()).unwrap();
+        current.add_connection(existing_eth2.clone()).unwrap();
+
+        let bond = Connection {
+            id: "bond0".to_string(),
+            interface: Some("bond0".to_string()),
+            config: ConnectionConfig::Bond(Default::default()),
+            ..Default::default()
+        };
+        let eth0 = Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            controller: Some(bond.uuid),
+            ..Default::default()
+        };
+        let eth1 = Connection {
+            id: "eth1".to_string(),
+            interface: Some("eth1".to_string()),
+            ..Default::default()
+        };
+        let eth2 = Connection {
+            id: "eth2".to_string(),
+            interface: Some("eth2".to_string()),
+            ..Default::default()
+        };
+        let mut state = NetworkState::default();
+        state.add_connection(bond).unwrap();
+        state.add_connection(eth0.clone()).unwrap();
+        state.add_connection(eth1.clone()).unwrap();
+        state.add_connection(eth2.clone()).unwrap();
+
+        let created = vec![existing_bond.uuid, existing_eth1.uuid];
+        reuse_existing_uuids(&mut state, &current, &created);
+
+        assert_eq!(state.connections[0].uuid, existing_bond.uuid);
+        assert_eq!(state.connections[1].uuid, eth0.uuid);
+        assert_eq!(state.connections[1].controller, Some(existing_bond.uuid));
+        assert_eq!(state.connections[2].uuid, eth1.uuid);
+        assert_eq!(state.connections[3].uuid, eth2.uuid);
+    }
+
+    #[test]
+    fn test_parse_nm_profile_paths() {
+        let lo = Uuid::new_v4();
+        let eth0 = Uuid::new_v4();
+        let generated = Uuid::new_v4();
+        let output = format!(
+            "{lo}:/run/NetworkManager/system-connections/lo.nmconnection\n\
+             {eth0}:/etc/NetworkManager/system-connections/eth0\\:1.nmconnection\n\
+             {generated}:\n"
+        );
+        let paths = parse_nm_profile_paths(&output);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(
+            paths[&lo],
+            PathBuf::from("/run/NetworkManager/system-connections/lo.nmconnection")
+        );
+        assert_eq!(
+            paths[&eth0],
+            PathBuf::from("/etc/NetworkManager/system-connections/eth0:1.nmconnection")
+        );
+    }
+
+    #[test]
+    fn test_parse_nm_version() {
+        assert_eq!(parse_nm_version("1.54.0-1.1\n"), Some((1, 54)));
+        assert_eq!(parse_nm_version("1.46.0"), Some((1, 46)));
+        assert_eq!(parse_nm_version(""), None);
+        assert_eq!(parse_nm_version_arg("1.44"), Ok((1, 44)));
+        assert!(parse_nm_version_arg("latest").is_err());
+    }
+
+    #[test]
+    fn test_write_networkstate_with_netconfig() {
+        let _ = MIGRATION_SETTINGS.set(crate::MigrationSettings::default());
+        let dir = std::env::temp_dir().join(format!("wicked2nm-test-{}", Uuid::new_v4()));
+        let mut state = NetworkState::default();
+        state
+            .add_connection(Connection {
+                id: "eth0".to_string(),
+                interface: Some("eth0".to_string()),
+                config: ConnectionConfig::Ethernet,
+                ..Default::default()
+            })
+            .unwrap();
+        let netconfig = Netconfig {
+            static_dns_servers: vec!["192.168.0.10".parse().unwrap()],
+            dns_policy: vec!["STATIC".to_string()],
+            ..Default::default()
+        };
+
+        write_networkstate(&mut state, Some(netconfig), &HashMap::new(), &dir).unwrap();
+
+        let lo = std::fs::read_to_string(dir.join("lo.nmconnection")).unwrap();
+        assert!(lo.contains("type=loopback"));
+        assert!(lo.contains("dns=192.168.0.10;"));
+        assert!(lo.contains("dns-priority=10"));
+        let eth0 = std::fs::read_to_string(dir.join("eth0.nmconnection")).unwrap();
+        assert!(eth0.contains("ignore-auto-dns=true"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_networkstate_stays_in_output_dir() {
+        let _ = MIGRATION_SETTINGS.set(crate::MigrationSettings::default());
+        let settings = MIGRATION_SETTINGS.get().unwrap();
+        let dir = std::env::temp_dir().join(format!("wicked2nm-test-{}", Uuid::new_v4()));
+        let mut state = NetworkState::default();
+        state
+            .add_connection(Connection {
+                id: "eth0".to_string(),
+                interface: Some("eth0".to_string()),
+                config: ConnectionConfig::Ethernet,
+                ..Default::default()
+            })
+            .unwrap();
+        let mut extra = ExtraSettings::default();
+        extra.set_sysctl("net/ipv6/conf/eth0/accept_redirects".to_string(), 0);
+        extra.lease_address4 = Some("192.168.122.226".parse().unwrap());
+        let extra_settings = HashMap::from([("eth0".to_string(), extra)]);
+
+        write_networkstate(&mut state, None, &extra_settings, &dir).unwrap();
+
+        let dropin = "70-wicked2nm-eth0.conf";
+        let contents = std::fs::read_to_string(dir.join("sysctl.d").join(dropin)).unwrap();
+        assert!(contents.contains("net/ipv6/conf/eth0/accept_redirects = 0"));
+        assert!(!settings.sysctl_dir.join(dropin).exists());
+        let lease = format!("internal-{}-eth0.lease", state.connections[0].uuid);
+        assert!(dir.join("leases").join(&lease).exists());
+        assert!(!settings.nm_lease_dir.join(&lease).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
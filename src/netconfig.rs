@@ -1,3 +1,5 @@
+use crate::diagnostic::Diagnostic;
+use crate::report::Findings;
 use agama_network::{model::Connection, NetworkState};
 use globset::Glob;
 use serde::{Deserialize, Serialize};
@@ -8,7 +10,9 @@ pub struct Netconfig {
     pub static_dns_servers: Vec<IpAddr>,
     pub static_dns_searchlist: Option<Vec<String>>,
     pub dns_policy: Vec<String>,
-    pub has_warning: bool,
+    pub check_duplicate_ip: Option<bool>,
+    #[serde(skip)]
+    pub findings: Findings,
 }
 
 pub fn read_netconfig(path: impl AsRef<Path>) -> Result<Netconfig, anyhow::Error> {
@@ -32,8 +36,11 @@ fn handle_netconfig_values() -> Result<Netconfig, anyhow::Error> {
                 .map(|(before, _)| before.to_string())
                 .unwrap_or(dns_policy);
             if dns_policy.contains(&"STATIC_FALLBACK".to_string()) {
-                log::warn!("NETCONFIG_DNS_POLICY \"STATIC_FALLBACK\" is not supported");
-                netconfig.has_warning = true;
+                netconfig.findings.warn(
+                    Diagnostic::NetconfigStaticFallback,
+                    Some("NETCONFIG_DNS_POLICY"),
+                    "NETCONFIG_DNS_POLICY \"STATIC_FALLBACK\" is not supported",
+                );
                 // Replace STATIC_FALLBACK with STATIC at the end to somewhat mimic behaviour
                 dns_policy = dns_policy.replace("STATIC_FALLBACK", "");
                 dns_policy.push_str(" STATIC");
@@ -51,8 +58,11 @@ fn handle_netconfig_values() -> Result<Netconfig, anyhow::Error> {
                 .filter_map(|ip_str| match ip_str.parse::<IpAddr>() {
                     Ok(x) => Some(x),
                     Err(_e) => {
-                        log::warn!("Invalid value '{ip_str}' in NETCONFIG_DNS_STATIC_SERVERS");
-                        netconfig.has_warning = true;
+                        netconfig.findings.warn(
+                            Diagnostic::NetconfigInvalidDnsServer,
+                            Some("NETCONFIG_DNS_STATIC_SERVERS"),
+                            format!("Invalid value '{ip_str}' in NETCONFIG_DNS_STATIC_SERVERS"),
+                        );
                         None
                     }
                 })
@@ -70,10 +80,21 @@ fn handle_netconfig_values() -> Result<Netconfig, anyhow::Error> {
         }
     }
 
+    if let Ok(check_duplicate_ip) = dotenv::var("CHECK_DUPLICATE_IP") {
+        match check_duplicate_ip.as_str() {
+            "yes" => netconfig.check_duplicate_ip = Some(true),
+            "no" => netconfig.check_duplicate_ip = Some(false),
+            _ => {}
+        }
+    }
+
     if let Ok(gratuitous_arp) = dotenv::var("SEND_GRATUITOUS_ARP") {
         if !gratuitous_arp.eq("auto") {
-            log::warn!("SEND_GRATUITOUS_ARP differs from 'auto', consider net.ipv4.conf.{{all,default}}.arp_notify variable in /etc/sysctl.conf");
-            netconfig.has_warning = true;
+            netconfig.findings.warn(
+                Diagnostic::NetconfigGratuitousArp,
+                Some("SEND_GRATUITOUS_ARP"),
+                "SEND_GRATUITOUS_ARP differs from 'auto', consider net.ipv4.conf.{all,default}.arp_notify variable in /etc/sysctl.conf",
+            );
         }
     }
 
@@ -132,10 +153,10 @@ mod tests {
     #[test]
     fn test_handle_netconfig_values() {
         env::set_var("NETCONFIG_DNS_POLICY", "STATIC_FALLBACK NetworkManager");
-        assert!(handle_netconfig_values().unwrap().has_warning);
+        assert!(handle_netconfig_values().unwrap().findings.has_warnings());
 
         env::set_var("NETCONFIG_DNS_POLICY", "STATIC_FALLBACK");
-        assert!(handle_netconfig_values().unwrap().has_warning);
+        assert!(handle_netconfig_values().unwrap().findings.has_warnings());
 
         // STATIC_FALLBACK should be replaced with STATIC at the end but contain a warning
         // NetworkManager should have the effect that everything after (eth1) is ignored
@@ -143,13 +164,11 @@ mod tests {
             "NETCONFIG_DNS_POLICY",
             "STATIC_FALLBACK eth0 NetworkManager eth1",
         );
+        let netconfig = handle_netconfig_values().unwrap();
+        assert!(netconfig.findings.has_warnings());
         assert_eq!(
-            handle_netconfig_values().unwrap(),
-            Netconfig {
-                dns_policy: vec!["eth0".to_string(), "STATIC".to_string()],
-                has_warning: true,
-                ..Default::default()
-            }
+            netconfig.dns_policy,
+            vec!["eth0".to_string(), "STATIC".to_string()]
         );
 
         env::set_var("NETCONFIG_DNS_POLICY", "");
@@ -198,6 +217,13 @@ mod tests {
             handle_netconfig_values().unwrap().dns_policy,
             vec!["STATIC".to_string(), "eth*".to_string(), "ppp?".to_string()]
         );
+
+        env::set_var("CHECK_DUPLICATE_IP", "no");
+        assert_eq!(
+            handle_netconfig_values().unwrap().check_duplicate_ip,
+            Some(false)
+        );
+        env::remove_var("CHECK_DUPLICATE_IP");
     }
 
     #[test]
@@ -1,3 +1,5 @@
+use crate::diagnostic::Diagnostic;
+use crate::report::Findings;
 use serde::Serialize;
 use std::path::Path;
 
@@ -5,7 +7,8 @@ use std::path::Path;
 pub struct NetconfigDhcp {
     pub dhclient_hostname_option: HostnameOption,
     pub dhclient6_hostname_option: HostnameOption,
-    pub has_warning: bool,
+    #[serde(skip)]
+    pub findings: Findings,
 }
 
 #[derive(Default, Debug, PartialEq, Serialize)]
@@ -28,11 +31,13 @@ impl From<String> for HostnameOption {
 
 pub fn read_netconfig_dhcp(path: &Path) -> Result<NetconfigDhcp, anyhow::Error> {
     if !path.exists() {
-        log::warn!("Missing netconfig dhcp file {}", path.display());
-        return Ok(NetconfigDhcp {
-            has_warning: true,
-            ..Default::default()
-        });
+        let mut netconfig_dhcp = NetconfigDhcp::default();
+        netconfig_dhcp.findings.warn(
+            Diagnostic::NetconfigDhcpMissing,
+            None,
+            format!("Missing netconfig dhcp file {}", path.display()),
+        );
+        return Ok(netconfig_dhcp);
     }
     if let Err(e) = dotenv::from_filename(path) {
         anyhow::bail!(e);
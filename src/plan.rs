@@ -0,0 +1,302 @@
+use crate::extra::ExtraSettings;
+use crate::keyfile::{add_extra_settings, to_keyfile};
+use agama_network::model::Connection;
+use agama_network::NetworkState;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug, PartialEq)]
+pub enum PlanAction {
+    Create,
+    Update,
+    Unchanged,
+    /// A connection with the same id or interface but a different uuid already exists.
+    Conflict {
+        id: String,
+        uuid: Uuid,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PropertyChange {
+    pub property: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ConnectionPlan {
+    pub id: String,
+    pub uuid: Uuid,
+    pub action: PlanAction,
+    pub changes: Vec<PropertyChange>,
+    /// Properties set with `nmcli` that can't be read back from NetworkManager, so they aren't
+    /// compared with the existing connection
+    pub not_compared: BTreeMap<String, String>,
+}
+
+impl fmt::Display for ConnectionPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.action {
+            PlanAction::Create => writeln!(f, "+ {} ({}) will be created", self.id, self.uuid)?,
+            PlanAction::Update => writeln!(f, "~ {} ({}) will be updated", self.id, self.uuid)?,
+            PlanAction::Unchanged => writeln!(f, "  {} ({}) is unchanged", self.id, self.uuid)?,
+            PlanAction::Conflict { id, uuid } => writeln!(
+                f,
+                "! {} ({}) conflicts with existing connection {id} ({uuid})",
+                self.id, self.uuid
+            )?,
+        }
+
+        for change in &self.changes {
+            match (&change.old, &change.new) {
+                (None, Some(new)) => writeln!(f, "    + {} = {new}", change.property)?,
+                (Some(old), None) => writeln!(f, "    - {} = {old}", change.property)?,
+                (Some(old), Some(new)) => {
+                    writeln!(f, "    ~ {} = {old} -> {new}", change.property)?
+                }
+                (None, None) => {}
+            }
+        }
+        for (property, value) in &self.not_compared {
+            writeln!(f, "    ? {property} = {value} (not compared)")?;
+        }
+        Ok(())
+    }
+}
+
+fn diff_properties(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<PropertyChange> {
+    let mut changes = vec![];
+
+    for (property, value) in new {
+        match old.get(property) {
+            Some(old_value) if old_value == value => {}
+            old_value => changes.push(PropertyChange {
+                property: property.clone(),
+                old: old_value.cloned(),
+                new: Some(value.clone()),
+            }),
+        }
+    }
+    for (property, value) in old {
+        if !new.contains_key(property) {
+            changes.push(PropertyChange {
+                property: property.clone(),
+                old: Some(value.clone()),
+                new: None,
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| a.property.cmp(&b.property));
+    changes
+}
+
+fn find_conflict<'a>(current: &'a NetworkState, connection: &Connection) -> Option<&'a Connection> {
+    current.connections.iter().find(|c| {
+        c.uuid != connection.uuid
+            && (c.id == connection.id
+                || (c.interface.is_some() && c.interface == connection.interface))
+    })
+}
+
+/// Compares every connection of `new` with the current NetworkManager state on property level.
+///
+/// The properties of the extra settings are only known for the new connections, for existing
+/// connections they are listed as not compared instead of always showing up as changes.
+pub fn plan(
+    current: &NetworkState,
+    new: &NetworkState,
+    extra_settings: &HashMap<String, ExtraSettings>,
+) -> Result<Vec<ConnectionPlan>, anyhow::Error> {
+    let mut plans = vec![];
+
+    for connection in &new.connections {
+        let mut new_keyfile = to_keyfile(connection, &new.connections)?;
+        let base_properties = new_keyfile.properties();
+        if let Some(extra) = extra_settings.get(&connection.id) {
+            add_extra_settings(&mut new_keyfile, extra);
+        }
+        let new_properties = new_keyfile.properties();
+        let extra_properties: BTreeMap<String, String> = new_properties
+            .iter()
+            .filter(|(property, value)| base_properties.get(*property) != Some(*value))
+            .map(|(property, value)| (property.clone(), value.clone()))
+            .collect();
+
+        let (action, existing) = if let Some(existing) = current
+            .connections
+            .iter()
+            .find(|c| c.uuid == connection.uuid)
+        {
+            (PlanAction::Update, Some(existing))
+        } else if let Some(existing) = find_conflict(current, connection) {
+            (
+                PlanAction::Conflict {
+                    id: existing.id.clone(),
+                    uuid: existing.uuid,
+                },
+                Some(existing),
+            )
+        } else {
+            (PlanAction::Create, None)
+        };
+
+        let (changes, not_compared) = match existing {
+            Some(existing) => {
+                let mut old_properties = to_keyfile(existing, &current.connections)?.properties();
+                old_properties.retain(|property, _| !extra_properties.contains_key(property));
+                let mut new_properties = new_properties;
+                new_properties.retain(|property, _| !extra_properties.contains_key(property));
+                (
+                    diff_properties(&old_properties, &new_properties),
+                    extra_properties,
+                )
+            }
+            None => (
+                diff_properties(&BTreeMap::new(), &new_properties),
+                BTreeMap::new(),
+            ),
+        };
+        let action = match action {
+            PlanAction::Update if changes.is_empty() => PlanAction::Unchanged,
+            action => action,
+        };
+
+        plans.push(ConnectionPlan {
+            id: connection.id.clone(),
+            uuid: connection.uuid,
+            action,
+            changes,
+            not_compared,
+        });
+    }
+
+    Ok(plans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection(id: &str) -> Connection {
+        Connection {
+            id: id.to_string(),
+            interface: Some(id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_actions() {
+        let lo = connection("lo");
+        let unchanged = connection("eth2");
+        let existing_eth1 = connection("eth1");
+        let mut current = NetworkState::default();
+        current.add_connection(lo.clone()).unwrap();
+        current.add_connection(unchanged.clone()).unwrap();
+        current.add_connection(existing_eth1.clone()).unwrap();
+
+        let mut new_lo = lo.clone();
+        new_lo.ip_config.nameservers = vec!["192.168.0.10".parse().unwrap()];
+        let mut new = NetworkState::default();
+        new.add_connection(new_lo).unwrap();
+        new.add_connection(unchanged.clone()).unwrap();
+        new.add_connection(connection("eth0")).unwrap();
+        new.add_connection(connection("eth1")).unwrap();
+
+        let plans = plan(&current, &new, &HashMap::new()).unwrap();
+        assert_eq!(plans.len(), 4);
+        assert_eq!(plans[0].action, PlanAction::Update);
+        assert_eq!(
+            plans[0].changes,
+            vec![PropertyChange {
+                property: "ipv4.dns".to_string(),
+                old: None,
+                new: Some("192.168.0.10;".to_string()),
+            }]
+        );
+        assert_eq!(plans[1].action, PlanAction::Unchanged);
+        assert_eq!(plans[2].action, PlanAction::Create);
+        assert!(plans[2]
+            .changes
+            .iter()
+            .any(|c| c.property == "connection.id" && c.new == Some("eth0".to_string())));
+        assert_eq!(
+            plans[3].action,
+            PlanAction::Conflict {
+                id: "eth1".to_string(),
+                uuid: existing_eth1.uuid
+            }
+        );
+    }
+
+    #[test]
+    fn test_plan_already_migrated() {
+        let mut eth0 = connection("eth0");
+        eth0.ip_config.addresses = vec!["192.168.1.5/24".parse().unwrap()];
+        let mut current = NetworkState::default();
+        current.add_connection(eth0.clone()).unwrap();
+        let mut new = NetworkState::default();
+        new.add_connection(eth0.clone()).unwrap();
+
+        let mut extra = ExtraSettings::default();
+        extra.set("ethernet", "wake-on-lan", 64);
+        extra.set("ipv6", "addr-gen-mode", "eui64");
+        let extra_settings = HashMap::from([("eth0".to_string(), extra)]);
+
+        let plans = plan(&current, &new, &extra_settings).unwrap();
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].action, PlanAction::Unchanged);
+        assert!(plans[0].changes.is_empty());
+        assert_eq!(
+            plans[0].not_compared,
+            BTreeMap::from([
+                ("ethernet.wake-on-lan".to_string(), "64".to_string()),
+                ("ipv6.addr-gen-mode".to_string(), "eui64".to_string()),
+            ])
+        );
+        assert!(plans[0]
+            .to_string()
+            .ends_with("is unchanged\n    ? ethernet.wake-on-lan = 64 (not compared)\n    ? ipv6.addr-gen-mode = eui64 (not compared)\n"));
+
+        // New connections show the extra settings as changes
+        let plans = plan(&NetworkState::default(), &new, &extra_settings).unwrap();
+        assert_eq!(plans[0].action, PlanAction::Create);
+        assert!(plans[0].not_compared.is_empty());
+        assert!(plans[0]
+            .changes
+            .iter()
+            .any(|c| c.property == "ethernet.wake-on-lan"));
+    }
+
+    #[test]
+    fn test_plan_display() {
+        let plan = ConnectionPlan {
+            id: "lo".to_string(),
+            uuid: Uuid::nil(),
+            action: PlanAction::Update,
+            changes: vec![
+                PropertyChange {
+                    property: "ipv4.dns".to_string(),
+                    old: Some("10.0.0.1;".to_string()),
+                    new: Some("192.168.0.10;".to_string()),
+                },
+                PropertyChange {
+                    property: "ipv4.dns-search".to_string(),
+                    old: Some("suse.com;".to_string()),
+                    new: None,
+                },
+            ],
+            not_compared: BTreeMap::new(),
+        };
+        assert_eq!(
+            plan.to_string(),
+            "~ lo (00000000-0000-0000-0000-000000000000) will be updated\n    ~ ipv4.dns = 10.0.0.1; -> 192.168.0.10;\n    - ipv4.dns-search = suse.com;\n"
+        );
+    }
+}
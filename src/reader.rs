@@ -1,6 +1,11 @@
+use crate::diagnostic::Diagnostic;
+use crate::filter::InterfaceFilter;
+use crate::ifcfg::read_ifcfg;
 use crate::interface::Interface;
 use crate::netconfig::{read_netconfig, Netconfig};
 use crate::netconfig_dhcp::{read_netconfig_dhcp, NetconfigDhcp};
+use crate::report::Findings;
+use crate::wicked_state::{read_wicked_state, WickedState};
 use crate::MIGRATION_SETTINGS;
 
 use quick_xml::events::Event;
@@ -15,7 +20,8 @@ pub struct InterfacesResult {
     pub interfaces: Vec<Interface>,
     pub netconfig: Option<Netconfig>,
     pub netconfig_dhcp: Option<NetconfigDhcp>,
-    pub has_warnings: bool,
+    pub wicked_state: Option<WickedState>,
+    pub findings: Findings,
 }
 
 fn validate_xml(xml: &str) -> Result<(), anyhow::Error> {
@@ -54,16 +60,7 @@ fn validate_xml(xml: &str) -> Result<(), anyhow::Error> {
 
 // Define a list of fields that are ignored if present.
 // The list must be in alphabetical order.
-pub const IGNORED_FIELDS: &[&str] = &[
-    "ipv4.arp-notify",
-    "ipv4.forwarding",
-    "ipv6.accept-dad",
-    "ipv6.accept-ra",
-    "ipv6.addr-gen-mode",
-    "ipv6.autoconf",
-    "ipv6.forwarding",
-    "ipv6.stable-secret",
-];
+pub const IGNORED_FIELDS: &[&str] = &["ipv4.arp-notify", "ipv6.autoconf"];
 
 pub fn read_xml_file(path: PathBuf) -> Result<InterfacesResult, anyhow::Error> {
     let contents = match fs::read_to_string(path.clone()) {
@@ -102,7 +99,8 @@ pub fn deserialize_xml(contents: String) -> Result<InterfacesResult, anyhow::Err
         interfaces,
         netconfig: None,
         netconfig_dhcp: None,
-        has_warnings: false,
+        wicked_state: None,
+        findings: Findings::default(),
     };
 
     for e in unhandled_fields {
@@ -113,22 +111,26 @@ pub fn deserialize_xml(contents: String) -> Result<InterfacesResult, anyhow::Err
         if IGNORED_FIELDS.binary_search(&field).is_ok() {
             log::debug!("Ingnore field in interface {ifc_name}: {field}");
         } else {
-            log::warn!("Unhandled field in interface {ifc_name}: {field}");
-            result.has_warnings = true;
+            result.findings.warn_for(
+                ifc_name,
+                Diagnostic::UnhandledField,
+                Some(field),
+                format!("Unhandled field in interface {ifc_name}: {field}"),
+            );
         }
     }
 
     Ok(result)
 }
 
-fn replace_colons(colon_string: &str) -> String {
+pub fn replace_colons(colon_string: &str) -> String {
     let re = Regex::new(r"<([\/]?)(\w+):(\w+)\b").unwrap();
     let replaced = re.replace_all(colon_string, "<$1$2-$3").to_string();
     replaced
 }
 
 // https://stackoverflow.com/a/76820878
-fn list_files(path: impl AsRef<Path>, recursive: bool) -> std::io::Result<Vec<PathBuf>> {
+pub fn list_files(path: impl AsRef<Path>, recursive: bool) -> std::io::Result<Vec<PathBuf>> {
     let mut buf = vec![];
     let entries = read_dir(path)?;
 
@@ -150,6 +152,11 @@ fn list_files(path: impl AsRef<Path>, recursive: bool) -> std::io::Result<Vec<Pa
 }
 
 fn is_ifsysctl(path: &Path) -> bool {
+    is_sysconfig_file(path, "ifsysctl")
+}
+
+/// Whether `path` is a sysconfig file starting with `prefix` and not a backup.
+pub fn is_sysconfig_file(path: &Path, prefix: &str) -> bool {
     let invalid_suffix = [
         "~",
         ".old",
@@ -164,34 +171,43 @@ fn is_ifsysctl(path: &Path) -> bool {
     path.file_name()
         .and_then(|name| name.to_str())
         .map(|filename| {
-            filename.starts_with("ifsysctl")
-                && !invalid_suffix.iter().any(|e| filename.ends_with(e))
+            filename.starts_with(prefix) && !invalid_suffix.iter().any(|e| filename.ends_with(e))
         })
         .unwrap_or(false)
 }
 
-fn warn_on_deprecated_ifsysctl() -> Result<bool, anyhow::Error> {
+fn warn_on_deprecated_ifsysctl() -> Result<Findings, anyhow::Error> {
     let settings = MIGRATION_SETTINGS.get().unwrap();
-    let mut has_warning = false;
+    let mut findings = Findings::default();
 
     let files = list_files(&settings.netconfig_base_dir, false)?;
     for file in files {
         if is_ifsysctl(&file) {
-            log::warn!(
-                "ifsysctl file \"{}\" is deprecated and will not be migrated",
-                file.display()
+            findings.warn(
+                Diagnostic::IfsysctlDeprecated,
+                None,
+                format!(
+                    "ifsysctl file \"{}\" is deprecated and will not be migrated",
+                    file.display()
+                ),
             );
-            has_warning = true;
         }
     }
 
-    Ok(has_warning)
+    Ok(findings)
 }
 
 pub fn read(paths: Vec<String>) -> Result<InterfacesResult, anyhow::Error> {
     let settings = MIGRATION_SETTINGS.get().unwrap();
 
-    let mut result = if paths.len() == 1 && paths[0] == "-" {
+    let mut result = if settings.ifcfg {
+        let paths: Vec<PathBuf> = if paths.is_empty() {
+            vec![settings.netconfig_base_dir.clone()]
+        } else {
+            paths.into_iter().map(PathBuf::from).collect()
+        };
+        read_ifcfg(&paths)?
+    } else if paths.len() == 1 && paths[0] == "-" {
         deserialize_xml(io::read_to_string(io::stdin())?)?
     } else {
         read_files(paths)?
@@ -199,8 +215,20 @@ pub fn read(paths: Vec<String>) -> Result<InterfacesResult, anyhow::Error> {
 
     if settings.with_netconfig {
         match read_netconfig(settings.netconfig_path.clone()) {
-            Ok(netconfig) => {
-                result.has_warnings |= netconfig.has_warning;
+            Ok(mut netconfig) => {
+                result
+                    .findings
+                    .append(std::mem::take(&mut netconfig.findings));
+                // CHECK_DUPLICATE_IP is the default for interfaces without their own arp-verify
+                if let Some(check_duplicate_ip) = netconfig.check_duplicate_ip {
+                    for interface in result
+                        .interfaces
+                        .iter_mut()
+                        .filter(|i| i.ipv4.arp_verify.is_none())
+                    {
+                        interface.ipv4.arp_verify = Some(check_duplicate_ip);
+                    }
+                }
                 result.netconfig = Some(netconfig);
             }
             Err(e) => {
@@ -213,8 +241,10 @@ pub fn read(paths: Vec<String>) -> Result<InterfacesResult, anyhow::Error> {
         };
 
         match read_netconfig_dhcp(&settings.netconfig_dhcp_path) {
-            Ok(netconfig_dhcp) => {
-                result.has_warnings |= netconfig_dhcp.has_warning;
+            Ok(mut netconfig_dhcp) => {
+                result
+                    .findings
+                    .append(std::mem::take(&mut netconfig_dhcp.findings));
                 result.netconfig_dhcp = Some(netconfig_dhcp);
             }
             Err(e) => {
@@ -228,22 +258,84 @@ pub fn read(paths: Vec<String>) -> Result<InterfacesResult, anyhow::Error> {
 
         match warn_on_deprecated_ifsysctl() {
             Err(e) => anyhow::bail!("Unexpected error, while searching ifsysctl files: {}", e),
-            Ok(has_warnings) => result.has_warnings |= has_warnings,
+            Ok(findings) => result.findings.append(findings),
         };
     }
 
+    if settings.read_wicked_state {
+        match read_wicked_state(&settings.wicked_state_dir) {
+            Ok(mut state) => {
+                if settings.import_leases {
+                    match state.read_leases(&settings.wicked_state_dir) {
+                        Ok(()) => {
+                            for interface in &state.leases6 {
+                                result.findings.info_for(
+                                    interface,
+                                    Diagnostic::Dhcp6Lease,
+                                    Some("ipv6_dhcp"),
+                                    format!(
+                                        "The DHCPv6 lease of {interface} isn't imported, the NetworkManager internal DHCP client doesn't persist DHCPv6 leases"
+                                    ),
+                                );
+                            }
+                        }
+                        Err(e) => result.findings.warn(
+                            Diagnostic::WickedState,
+                            None,
+                            format!(
+                                "Failed to read the DHCP leases in {}: {e}",
+                                settings.wicked_state_dir.display()
+                            ),
+                        ),
+                    }
+                }
+                result.wicked_state = Some(state);
+            }
+            Err(e) => result.findings.warn(
+                Diagnostic::WickedState,
+                None,
+                format!(
+                    "Failed to read the wicked state in {}, DHCP clients get a new identity: {e}",
+                    settings.wicked_state_dir.display()
+                ),
+            ),
+        }
+    }
+
     // Filter loopback as it doesn't need to be migrated
     result.interfaces.retain(|interface| interface.name != "lo");
 
+    check_ipv6_shared(
+        &result.interfaces,
+        &settings.ipv6_shared,
+        &mut result.findings,
+    );
+
+    InterfaceFilter::new(&settings.interfaces, &settings.exclude_interfaces)?.apply(&mut result)?;
+
     Ok(result)
 }
 
+/// Warns about `--ipv6-shared` interfaces that aren't in the configuration.
+fn check_ipv6_shared(interfaces: &[Interface], ipv6_shared: &[String], findings: &mut Findings) {
+    for name in ipv6_shared {
+        if !interfaces.iter().any(|i| i.name == *name) {
+            findings.warn(
+                Diagnostic::Ipv6SharedUnknown,
+                None,
+                format!("Unknown interface {name} in --ipv6-shared"),
+            );
+        }
+    }
+}
+
 fn read_files(file_paths: Vec<String>) -> Result<InterfacesResult, anyhow::Error> {
     let mut result = InterfacesResult {
         interfaces: vec![],
         netconfig: None,
         netconfig_dhcp: None,
-        has_warnings: false,
+        wicked_state: None,
+        findings: Findings::default(),
     };
 
     for path in file_paths {
@@ -260,12 +352,12 @@ fn read_files(file_paths: Vec<String>) -> Result<InterfacesResult, anyhow::Error
                     }
                 }
                 let mut read_xml = read_xml_file(file)?;
-                result.has_warnings |= read_xml.has_warnings;
+                result.findings.append(read_xml.findings);
                 result.interfaces.append(&mut read_xml.interfaces);
             }
         } else {
             let mut read_xml = read_xml_file(path)?;
-            result.has_warnings |= read_xml.has_warnings;
+            result.findings.append(read_xml.findings);
             result.interfaces.append(&mut read_xml.interfaces);
         }
     }
@@ -277,6 +369,27 @@ mod tests {
     use super::*;
     use crate::bond::*;
 
+    #[test]
+    fn test_check_ipv6_shared() {
+        let interfaces = vec![Interface {
+            name: "eth1".to_string(),
+            ..Default::default()
+        }];
+        let mut findings = Findings::default();
+        check_ipv6_shared(&interfaces, &["eth1".to_string()], &mut findings);
+        assert!(!findings.has_warnings());
+
+        check_ipv6_shared(
+            &interfaces,
+            &["eth1".to_string(), "eth2".to_string()],
+            &mut findings,
+        );
+        let warnings: Vec<_> = findings.iter().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, Diagnostic::Ipv6SharedUnknown);
+        assert!(warnings[0].message.contains("eth2"));
+    }
+
     #[test]
     fn test_bond_options_from_xml() {
         let xml = r##"
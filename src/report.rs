@@ -0,0 +1,354 @@
+use crate::diagnostic::Diagnostic;
+use crate::keyfile::connection_type;
+use crate::migrate::NetworkStateResult;
+use crate::reader::InterfacesResult;
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+use std::path::Path;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingLevel {
+    Warning,
+    Info,
+}
+
+/// A single warning or info encountered during the migration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub level: FindingLevel,
+    pub code: Diagnostic,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    /// The wicked field the finding refers to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Collects findings, every finding is also logged when it is added.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Findings(Vec<Finding>);
+
+impl Findings {
+    fn push(
+        &mut self,
+        level: FindingLevel,
+        code: Diagnostic,
+        interface: Option<&str>,
+        field: Option<&str>,
+        message: String,
+    ) {
+        match level {
+            FindingLevel::Warning => log::warn!("{message} [{code}]"),
+            FindingLevel::Info => log::info!("{message} [{code}]"),
+        }
+        self.0.push(Finding {
+            level,
+            code,
+            interface: interface.map(String::from),
+            field: field.map(String::from),
+            message,
+        });
+    }
+
+    pub fn warn(&mut self, code: Diagnostic, field: Option<&str>, message: impl Into<String>) {
+        self.push(FindingLevel::Warning, code, None, field, message.into());
+    }
+
+    pub fn info(&mut self, code: Diagnostic, field: Option<&str>, message: impl Into<String>) {
+        self.push(FindingLevel::Info, code, None, field, message.into());
+    }
+
+    /// Adds a warning for a specific interface.
+    pub fn warn_for(
+        &mut self,
+        interface: &str,
+        code: Diagnostic,
+        field: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        self.push(
+            FindingLevel::Warning,
+            code,
+            Some(interface),
+            field,
+            message.into(),
+        );
+    }
+
+    /// Adds an info for a specific interface.
+    pub fn info_for(
+        &mut self,
+        interface: &str,
+        code: Diagnostic,
+        field: Option<&str>,
+        message: impl Into<String>,
+    ) {
+        self.push(
+            FindingLevel::Info,
+            code,
+            Some(interface),
+            field,
+            message.into(),
+        );
+    }
+
+    /// Assigns all findings without an interface to `interface`.
+    pub fn set_interface(&mut self, interface: &str) {
+        for finding in self.0.iter_mut().filter(|f| f.interface.is_none()) {
+            finding.interface = Some(interface.to_string());
+        }
+    }
+
+    pub fn append(&mut self, mut other: Findings) {
+        self.0.append(&mut other.0);
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.0.iter().any(|f| f.level == FindingLevel::Warning)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Finding> {
+        self.0.iter()
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&Finding) -> bool) {
+        self.0.retain(f);
+    }
+
+    /// Returns the warnings that abort the migration.
+    ///
+    /// Denied codes always abort and allowed codes never do, all other
+    /// warnings abort unless the migration is continued on warnings.
+    pub fn blocking_warnings(
+        &self,
+        continue_migration: bool,
+        allowed: &[Diagnostic],
+        denied: &[Diagnostic],
+    ) -> Vec<&Finding> {
+        self.0
+            .iter()
+            .filter(|f| f.level == FindingLevel::Warning)
+            .filter(|f| {
+                if denied.contains(&f.code) {
+                    true
+                } else if allowed.contains(&f.code) {
+                    false
+                } else {
+                    !continue_migration
+                }
+            })
+            .collect()
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct ReportConnection {
+    pub id: String,
+    #[serde_as(as = "DisplayFromStr")]
+    pub uuid: Uuid,
+    #[serde(rename = "type")]
+    pub connection_type: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InterfaceReport {
+    pub name: String,
+    pub origin: String,
+    pub connections: Vec<ReportConnection>,
+    pub findings: Vec<Finding>,
+}
+
+/// Machine-readable summary of a migration.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub has_warnings: bool,
+    pub interfaces: Vec<InterfaceReport>,
+    /// Findings that don't belong to a specific interface, e.g. from netconfig
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    pub fn new(interfaces_result: &InterfacesResult, state_result: &NetworkStateResult) -> Self {
+        let findings = &state_result.findings;
+
+        let interfaces = interfaces_result
+            .interfaces
+            .iter()
+            .map(|interface| {
+                let uuids = state_result
+                    .interface_connections
+                    .get(&interface.name)
+                    .cloned()
+                    .unwrap_or_default();
+                let connections = state_result
+                    .network_state
+                    .connections
+                    .iter()
+                    .filter(|c| uuids.contains(&c.uuid))
+                    .map(|c| ReportConnection {
+                        id: c.id.clone(),
+                        uuid: c.uuid,
+                        connection_type: connection_type(&c.config).to_string(),
+                    })
+                    .collect();
+
+                InterfaceReport {
+                    name: interface.name.clone(),
+                    origin: interface.origin.clone(),
+                    connections,
+                    findings: findings
+                        .iter()
+                        .filter(|f| f.interface.as_ref() == Some(&interface.name))
+                        .cloned()
+                        .collect(),
+                }
+            })
+            .collect();
+
+        Report {
+            has_warnings: findings.has_warnings(),
+            interfaces,
+            findings: findings
+                .iter()
+                .filter(|f| {
+                    f.interface.as_ref().is_none_or(|name| {
+                        !interfaces_result.interfaces.iter().any(|i| &i.name == name)
+                    })
+                })
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Writes the report as JSON to `path` or stdout if `path` is `None`.
+    pub fn write_json(&self, path: Option<&Path>) -> Result<(), anyhow::Error> {
+        let json = serde_json::to_string_pretty(self)?;
+        match path {
+            Some(path) => std::fs::write(path, json)?,
+            None => println!("{json}"),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::Interface;
+    use agama_network::model::Connection;
+
+    #[test]
+    fn test_findings() {
+        let mut findings = Findings::default();
+        findings.info(Diagnostic::TeamConverted, None, "Some info");
+        assert!(!findings.has_warnings());
+
+        findings.warn(
+            Diagnostic::UnhandledField,
+            Some("ipv4.arp_verify"),
+            "Unhandled field",
+        );
+        findings.set_interface("eth0");
+        findings.warn_for("eth1", Diagnostic::TeamQueueId, None, "Other warning");
+        assert!(findings.has_warnings());
+
+        let interfaces: Vec<Option<&str>> =
+            findings.iter().map(|f| f.interface.as_deref()).collect();
+        assert_eq!(interfaces, vec![Some("eth0"), Some("eth0"), Some("eth1")]);
+    }
+
+    #[test]
+    fn test_blocking_warnings() {
+        let mut findings = Findings::default();
+        findings.info(Diagnostic::TeamConverted, None, "Some info");
+        findings.warn(Diagnostic::UnhandledField, None, "Unhandled field");
+        findings.warn(Diagnostic::TeamQueueId, None, "Queue id");
+
+        assert_eq!(findings.blocking_warnings(false, &[], &[]).len(), 2);
+        assert!(findings.blocking_warnings(true, &[], &[]).is_empty());
+
+        let blocking = findings.blocking_warnings(false, &[Diagnostic::TeamQueueId], &[]);
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].code, Diagnostic::UnhandledField);
+
+        let blocking = findings.blocking_warnings(true, &[], &[Diagnostic::TeamQueueId]);
+        assert_eq!(blocking.len(), 1);
+        assert_eq!(blocking[0].code, Diagnostic::TeamQueueId);
+
+        // Deny takes precedence over allow
+        let blocking = findings.blocking_warnings(
+            true,
+            &[Diagnostic::TeamQueueId],
+            &[Diagnostic::TeamQueueId],
+        );
+        assert_eq!(blocking.len(), 1);
+    }
+
+    #[test]
+    fn test_report_json() {
+        let connection = Connection {
+            id: "eth0".to_string(),
+            interface: Some("eth0".to_string()),
+            ..Default::default()
+        };
+        let mut state_result = NetworkStateResult::default();
+        state_result
+            .interface_connections
+            .insert("eth0".to_string(), vec![connection.uuid]);
+        state_result
+            .network_state
+            .add_connection(connection.clone())
+            .unwrap();
+        state_result.findings.warn_for(
+            "eth0",
+            Diagnostic::UnhandledField,
+            Some("ipv4.arp_verify"),
+            "Unhandled field",
+        );
+        state_result.findings.warn(
+            Diagnostic::NetconfigStaticFallback,
+            None,
+            "NETCONFIG_DNS_POLICY \"STATIC_FALLBACK\" is not supported",
+        );
+
+        let interfaces_result = InterfacesResult {
+            interfaces: vec![Interface {
+                name: "eth0".to_string(),
+                origin: "compat:suse:/etc/sysconfig/network/ifcfg-eth0".to_string(),
+                ..Default::default()
+            }],
+            netconfig: None,
+            netconfig_dhcp: None,
+            wicked_state: None,
+            findings: Findings::default(),
+        };
+
+        let report = Report::new(&interfaces_result, &state_result);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["has_warnings"], true);
+        assert_eq!(
+            json["interfaces"][0]["origin"],
+            "compat:suse:/etc/sysconfig/network/ifcfg-eth0"
+        );
+        assert_eq!(
+            json["interfaces"][0]["connections"][0]["uuid"],
+            connection.uuid.to_string()
+        );
+        assert_eq!(json["interfaces"][0]["connections"][0]["type"], "ethernet");
+        assert_eq!(
+            json["interfaces"][0]["findings"][0]["field"],
+            "ipv4.arp_verify"
+        );
+        assert_eq!(json["findings"][0]["level"], "warning");
+        assert_eq!(
+            json["findings"][0]["code"],
+            "W2NM-NETCONFIG-STATIC-FALLBACK"
+        );
+        assert!(json["findings"][0].get("interface").is_none());
+    }
+}
@@ -0,0 +1,116 @@
+use crate::extra::ExtraSettings;
+use agama_network::model::Connection;
+use anyhow::Context;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A sysctl.d drop-in with the kernel settings of one interface NetworkManager can't set.
+///
+/// The keys use `/` as separator so interface names containing dots (e.g. `eth0.10`) work.
+#[derive(Debug, PartialEq)]
+pub struct SysctlDropin {
+    pub path: PathBuf,
+    pub sysctls: BTreeMap<String, String>,
+}
+
+impl SysctlDropin {
+    pub fn contents(&self) -> String {
+        let mut contents = String::from("# Migrated from wicked by wicked2nm\n");
+        for (key, value) in &self.sysctls {
+            contents.push_str(&format!("{key} = {value}\n"));
+        }
+        contents
+    }
+
+    pub fn write(&self) -> Result<(), anyhow::Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, self.contents())
+            .with_context(|| format!("Couldn't write sysctl drop-in {}", self.path.display()))
+    }
+
+    /// Sets the values on the running system, interfaces that don't exist yet get them from
+    /// the drop-in once they appear.
+    pub fn apply(&self) {
+        for (key, value) in &self.sysctls {
+            let path = Path::new("/proc/sys").join(key);
+            if !path.exists() {
+                log::debug!("Skipping sysctl {key}, it doesn't exist (yet)");
+                continue;
+            }
+            if let Err(e) = fs::write(&path, value) {
+                log::warn!("Failed to set sysctl {key} = {value}: {e}");
+            }
+        }
+    }
+}
+
+/// Collects the sysctls of the connections into one drop-in per interface in `dir`.
+pub fn sysctl_dropins(
+    connections: &[Connection],
+    extra_settings: &HashMap<String, ExtraSettings>,
+    dir: &Path,
+) -> Vec<SysctlDropin> {
+    let mut sysctls: BTreeMap<&str, BTreeMap<String, String>> = BTreeMap::new();
+    for connection in connections {
+        let Some(extra) = extra_settings.get(&connection.id) else {
+            continue;
+        };
+        if extra.sysctls.is_empty() {
+            continue;
+        }
+        let interface = connection.interface.as_deref().unwrap_or(&connection.id);
+        sysctls
+            .entry(interface)
+            .or_default()
+            .extend(extra.sysctls.clone());
+    }
+
+    sysctls
+        .into_iter()
+        .map(|(interface, sysctls)| SysctlDropin {
+            path: dir.join(format!("70-wicked2nm-{interface}.conf")),
+            sysctls,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sysctl_dropins() {
+        let connections = vec![
+            Connection {
+                id: "eth0.10".to_string(),
+                interface: Some("eth0.10".to_string()),
+                ..Default::default()
+            },
+            Connection {
+                id: "eth1".to_string(),
+                interface: Some("eth1".to_string()),
+                ..Default::default()
+            },
+        ];
+        let mut extra = ExtraSettings::default();
+        extra.set_sysctl("net/ipv6/conf/eth0.10/accept_redirects".to_string(), 0);
+        let extra_settings = HashMap::from([
+            ("eth0.10".to_string(), extra),
+            ("eth1".to_string(), ExtraSettings::default()),
+        ]);
+
+        let dropins = sysctl_dropins(&connections, &extra_settings, Path::new("/etc/sysctl.d"));
+        assert_eq!(dropins.len(), 1);
+        assert_eq!(
+            dropins[0].path,
+            PathBuf::from("/etc/sysctl.d/70-wicked2nm-eth0.10.conf")
+        );
+        assert_eq!(
+            dropins[0].contents(),
+            "# Migrated from wicked by wicked2nm\nnet/ipv6/conf/eth0.10/accept_redirects = 0\n"
+        );
+    }
+}
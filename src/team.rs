@@ -1,3 +1,5 @@
+use crate::diagnostic::Diagnostic;
+use crate::report::Findings;
 use agama_network::model::{self};
 use agama_network::types::BondMode as AgamaBondMode;
 use serde::{Deserialize, Serialize};
@@ -158,11 +160,11 @@ fn gcd(mut a: u32, mut b: u32) -> u32 {
 }
 
 impl Team {
-    /// Convert Team configuration to bond ConnectionConfig, returning (config, has_warnings)
-    pub fn to_connection_config(&self) -> (model::ConnectionConfig, bool) {
+    /// Convert Team configuration to bond ConnectionConfig, returning (config, findings)
+    pub fn to_connection_config(&self) -> (model::ConnectionConfig, Findings) {
         let mut bond_options: HashMap<String, String> = HashMap::new();
         let mut mode = AgamaBondMode::RoundRobin; // Default fallback
-        let mut has_warnings = false;
+        let mut findings = Findings::default();
 
         // Handle top-level team options
         if let Some(notify_peers) = &self.notify_peers {
@@ -180,16 +182,20 @@ impl Team {
                 bond_options.insert(String::from("resend_igmp"), count.to_string());
             }
             if mcast_rejoin.interval.is_some() {
-                log::warn!(
-                    "Team 'mcast_rejoin.interval' is not supported in bond configuration - bond uses hardcoded 200ms interval"
+                findings.warn(
+                    Diagnostic::TeamMcastRejoinInterval,
+                    Some("team.mcast_rejoin.interval"),
+                    "Team 'mcast_rejoin.interval' is not supported in bond configuration - bond uses hardcoded 200ms interval",
                 );
-                has_warnings = true;
             }
         }
 
         if self.link_watch_policy.is_some() {
-            log::warn!("Team 'link_watch_policy' is not supported in bond configuration");
-            has_warnings = true;
+            findings.warn(
+                Diagnostic::TeamLinkWatchPolicy,
+                Some("team.link_watch_policy"),
+                "Team 'link_watch_policy' is not supported in bond configuration",
+            );
         }
 
         if let Some(runner) = &self.runner {
@@ -207,7 +213,11 @@ impl Team {
 
                     // Bond's ad_actor_sys_prio valid range is 1-65535, team allows 0-65535
                     let sys_prio = if runner.sys_prio == 0 {
-                        log::info!("Team sys_prio '0' converted to '1' - bond range starts at 1");
+                        findings.info(
+                            Diagnostic::TeamSysPrio,
+                            Some("team.runner.sys_prio"),
+                            "Team sys_prio '0' converted to '1' - bond range starts at 1",
+                        );
                         1
                     } else {
                         runner.sys_prio
@@ -218,28 +228,39 @@ impl Team {
 
                     let val = match runner.select_policy {
                         SelectPolicy::LacpPrio => {
-                            log::info!("Team select_policy 'lacp_prio' approximated to bond ad_select 'stable'.");
+                            findings.info(
+                                Diagnostic::TeamSelectPolicy,
+                                Some("team.runner.select_policy"),
+                                "Team select_policy 'lacp_prio' approximated to bond ad_select 'stable'.",
+                            );
                             "stable"
                         }
                         SelectPolicy::LacpPrioStable => {
-                            log::info!("Team select_policy 'lacp_prio_stable' approximated to bond ad_select 'stable'.");
+                            findings.info(
+                                Diagnostic::TeamSelectPolicy,
+                                Some("team.runner.select_policy"),
+                                "Team select_policy 'lacp_prio_stable' approximated to bond ad_select 'stable'.",
+                            );
                             "stable"
                         }
                         SelectPolicy::Bandwidth => "bandwidth",
                         SelectPolicy::Count => "count",
                         SelectPolicy::PortOptions => {
-                            log::warn!(
-                                "Team select_policy 'port_options' not supported in kernel 6.12 - requires kernel 6.18+ (actor_port_prio). Using 'stable' instead."
+                            findings.warn(
+                                Diagnostic::TeamSelectPolicy,
+                                Some("team.runner.select_policy"),
+                                "Team select_policy 'port_options' not supported in kernel 6.12 - requires kernel 6.18+ (actor_port_prio). Using 'stable' instead.",
                             );
-                            has_warnings = true;
                             "stable"
                         }
                     };
                     bond_options.insert(String::from("ad_select"), val.to_string());
 
                     if runner.tx_balancer.is_some() {
-                        log::info!(
-                            "Team LACP with tx_balancer converted to bond 802.3ad - dynamic flow rebalancing not available in bond"
+                        findings.info(
+                            Diagnostic::TeamTxBalancer,
+                            Some("team.runner.tx_balancer"),
+                            "Team LACP with tx_balancer converted to bond 802.3ad - dynamic flow rebalancing not available in bond",
                         );
                     }
                 }
@@ -269,7 +290,11 @@ impl Team {
                     }
                 }
                 RunnerName::Random => {
-                    log::info!("Team runner 'random' approximated by bond mode 'balance-rr' with packets_per_slave=0");
+                    findings.info(
+                        Diagnostic::TeamRunnerRandom,
+                        Some("team.runner.name"),
+                        "Team runner 'random' approximated by bond mode 'balance-rr' with packets_per_slave=0",
+                    );
                     mode = AgamaBondMode::RoundRobin;
                     bond_options.insert(String::from("packets_per_slave"), String::from("0"));
                 }
@@ -328,11 +353,13 @@ impl Team {
                 let missing: Vec<_> = team_set.difference(&bond_set).map(|&&s| s).collect();
 
                 if !missing.is_empty() {
-                    log::info!(
-                        "Team tx_hash '{}' mapped to bond xmit_hash_policy '{}' - elements not covered in this conversion: {}",
-                        tx_hash,
-                        best_policy,
-                        missing.join(", ")
+                    findings.info(
+                        Diagnostic::TeamTxHash,
+                        Some("team.runner.tx_hash"),
+                        format!(
+                            "Team tx_hash '{tx_hash}' mapped to bond xmit_hash_policy '{best_policy}' - elements not covered in this conversion: {}",
+                            missing.join(", ")
+                        ),
                     );
                 }
             // If balancer is set it should fall back to the team default: ["eth", "ipv4", "ipv6"] → layer2+3
@@ -360,8 +387,11 @@ impl Team {
 
             // Warn if we have ethtool AND (arp_ping OR nsna_ping)
             if has_ethtool && (has_arp_ping || has_nsna_ping) {
-                log::warn!("Team has both ethtool and arp_ping/nsna_ping watches - bond supports only one monitoring method. Using miimon (ethtool), ignoring arp_ping/nsna_ping.");
-                has_warnings = true;
+                findings.warn(
+                    Diagnostic::TeamLinkWatchMultiple,
+                    Some("team.link_watch"),
+                    "Team has both ethtool and arp_ping/nsna_ping watches - bond supports only one monitoring method. Using miimon (ethtool), ignoring arp_ping/nsna_ping.",
+                );
             }
 
             // Track if we've already processed ethtool or warned about unsupported fields
@@ -374,7 +404,11 @@ impl Team {
                 match watch.name {
                     WatchName::Ethtool => {
                         if ethtool_done {
-                            log::info!("Team has multiple ethtool watches - bond only supports one. Using first watch's settings. Behavior may differ from team's link_watch_policy.");
+                            findings.info(
+                                Diagnostic::TeamLinkWatchMultiple,
+                                Some("team.link_watch"),
+                                "Team has multiple ethtool watches - bond only supports one. Using first watch's settings. Behavior may differ from team's link_watch_policy.",
+                            );
                             continue;
                         }
 
@@ -406,8 +440,11 @@ impl Team {
                         // Handle interval - insert if not present, warn if different
                         if let Some(existing_interval) = bond_options.get("arp_interval") {
                             if existing_interval != &watch.interval.to_string() {
-                                log::warn!("Team has multiple arp_ping/nsna_ping watches with different intervals - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's interval.");
-                                has_warnings = true;
+                                findings.warn(
+                                    Diagnostic::TeamLinkWatchMismatch,
+                                    Some("team.link_watch.interval"),
+                                    "Team has multiple arp_ping/nsna_ping watches with different intervals - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's interval.",
+                                );
                             }
                         } else {
                             bond_options
@@ -428,8 +465,11 @@ impl Team {
 
                             if let Some(existing_validate) = bond_options.get("arp_validate") {
                                 if existing_validate != arp_validate {
-                                    log::warn!("Team has multiple arp_ping watches with different validation settings - bond only supports one value. Using first watch's validation.");
-                                    has_warnings = true;
+                                    findings.warn(
+                                        Diagnostic::TeamLinkWatchMismatch,
+                                        Some("team.link_watch.validate_active"),
+                                        "Team has multiple arp_ping watches with different validation settings - bond only supports one value. Using first watch's validation.",
+                                    );
                                 }
                             } else {
                                 bond_options
@@ -453,8 +493,11 @@ impl Team {
                         if watch.missed_max > 0 {
                             if let Some(existing_missed_max) = bond_options.get("arp_missed_max") {
                                 if existing_missed_max != &watch.missed_max.to_string() {
-                                    log::warn!("Team has multiple arp_ping/nsna_ping watches with different missed_max values - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's missed_max.");
-                                    has_warnings = true;
+                                    findings.warn(
+                                        Diagnostic::TeamLinkWatchMismatch,
+                                        Some("team.link_watch.missed_max"),
+                                        "Team has multiple arp_ping/nsna_ping watches with different missed_max values - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's missed_max.",
+                                    );
                                 }
                             } else {
                                 bond_options.insert(
@@ -467,22 +510,32 @@ impl Team {
                         // Warn about unsupported ARP ping fields (only once)
                         if !arp_warnings_done {
                             if watch.source_host.is_some() {
-                                log::warn!("Team ARP ping 'source_host' is not supported in bond configuration");
-                                has_warnings = true;
+                                findings.warn(
+                                    Diagnostic::TeamLinkWatchUnsupported,
+                                    Some("team.link_watch.source_host"),
+                                    "Team ARP ping 'source_host' is not supported in bond configuration",
+                                );
                             }
                             if watch.init_wait > 0 {
-                                log::warn!("Team ARP ping 'init_wait' is not supported in bond configuration");
-                                has_warnings = true;
+                                findings.warn(
+                                    Diagnostic::TeamLinkWatchUnsupported,
+                                    Some("team.link_watch.init_wait"),
+                                    "Team ARP ping 'init_wait' is not supported in bond configuration",
+                                );
                             }
                             if watch.send_always.is_some() {
-                                log::warn!("Team ARP ping 'send_always' is not supported in bond configuration");
-                                has_warnings = true;
+                                findings.warn(
+                                    Diagnostic::TeamLinkWatchUnsupported,
+                                    Some("team.link_watch.send_always"),
+                                    "Team ARP ping 'send_always' is not supported in bond configuration",
+                                );
                             }
                             if watch.vlanid.is_some() {
-                                log::warn!(
-                                    "Team ARP ping 'vlanid' is not supported in bond configuration"
+                                findings.warn(
+                                    Diagnostic::TeamLinkWatchUnsupported,
+                                    Some("team.link_watch.vlanid"),
+                                    "Team ARP ping 'vlanid' is not supported in bond configuration",
                                 );
-                                has_warnings = true;
                             }
                             arp_warnings_done = true;
                         }
@@ -491,8 +544,11 @@ impl Team {
                         // Handle interval - insert if not present, warn if different (shared with arp_ping)
                         if let Some(existing_interval) = bond_options.get("arp_interval") {
                             if existing_interval != &watch.interval.to_string() {
-                                log::warn!("Team has multiple arp_ping/nsna_ping watches with different intervals - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's interval.");
-                                has_warnings = true;
+                                findings.warn(
+                                    Diagnostic::TeamLinkWatchMismatch,
+                                    Some("team.link_watch.interval"),
+                                    "Team has multiple arp_ping/nsna_ping watches with different intervals - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's interval.",
+                                );
                             }
                         } else {
                             bond_options
@@ -515,8 +571,11 @@ impl Team {
                         if watch.missed_max > 0 {
                             if let Some(existing_missed_max) = bond_options.get("arp_missed_max") {
                                 if existing_missed_max != &watch.missed_max.to_string() {
-                                    log::warn!("Team has multiple arp_ping/nsna_ping watches with different missed_max values - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's missed_max.");
-                                    has_warnings = true;
+                                    findings.warn(
+                                        Diagnostic::TeamLinkWatchMismatch,
+                                        Some("team.link_watch.missed_max"),
+                                        "Team has multiple arp_ping/nsna_ping watches with different missed_max values - bond only supports one value (shared for both IPv4 and IPv6). Using first watch's missed_max.",
+                                    );
                                 }
                             } else {
                                 bond_options.insert(
@@ -529,15 +588,21 @@ impl Team {
                         // Warn about unsupported NS/NA ping fields (only once)
                         if !nsna_warnings_done {
                             if watch.init_wait > 0 {
-                                log::warn!("Team NS/NA ping 'init_wait' is not supported in bond configuration");
-                                has_warnings = true;
+                                findings.warn(
+                                    Diagnostic::TeamLinkWatchUnsupported,
+                                    Some("team.link_watch.init_wait"),
+                                    "Team NS/NA ping 'init_wait' is not supported in bond configuration",
+                                );
                             }
                             nsna_warnings_done = true;
                         }
                     }
                     WatchName::Tipc => {
-                        log::warn!("Team link watch 'tipc' is not supported in bond configuration");
-                        has_warnings = true;
+                        findings.warn(
+                            Diagnostic::TeamLinkWatchUnsupported,
+                            Some("team.link_watch.name"),
+                            "Team link watch 'tipc' is not supported in bond configuration",
+                        );
                     }
                     _ => {} // Skip non-preferred watches (e.g., arp_ping when ethtool exists)
                 }
@@ -548,7 +613,7 @@ impl Team {
             options: model::BondOptions(bond_options),
             mode,
         });
-        (config, has_warnings)
+        (config, findings)
     }
 }
 
@@ -876,8 +941,8 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(!has_warnings, "Single nsna_ping should not warn");
+        let (config, findings) = team.to_connection_config();
+        assert!(!findings.has_warnings(), "Single nsna_ping should not warn");
 
         if let ConnectionConfig::Bond(bond) = config {
             assert_eq!(bond.options.0.get("arp_interval").unwrap(), "100");
@@ -909,8 +974,8 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(!has_warnings, "Same interval should not warn");
+        let (config, findings) = team.to_connection_config();
+        assert!(!findings.has_warnings(), "Same interval should not warn");
 
         if let ConnectionConfig::Bond(bond) = config {
             assert_eq!(bond.options.0.get("arp_interval").unwrap(), "1000");
@@ -941,8 +1006,8 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(has_warnings, "Different intervals should warn");
+        let (config, findings) = team.to_connection_config();
+        assert!(findings.has_warnings(), "Different intervals should warn");
 
         if let ConnectionConfig::Bond(bond) = config {
             assert_eq!(bond.options.0.get("arp_interval").unwrap(), "1000");
@@ -973,9 +1038,9 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
+        let (config, findings) = team.to_connection_config();
         assert!(
-            !has_warnings,
+            !findings.has_warnings(),
             "Multiple nsna_ping with same interval should not warn"
         );
 
@@ -1021,8 +1086,11 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(has_warnings, "Should warn about mcast_rejoin.interval");
+        let (config, findings) = team.to_connection_config();
+        assert!(
+            findings.has_warnings(),
+            "Should warn about mcast_rejoin.interval"
+        );
         if let ConnectionConfig::Bond(bond) = config {
             assert_eq!(bond.options.0.get("resend_igmp").unwrap(), "5");
             // interval is ignored
@@ -1252,8 +1320,11 @@ mod tests {
             ..Default::default()
         };
 
-        let (_, has_warnings) = team_with_warnings.to_connection_config();
-        assert!(has_warnings, "Expected warnings for unsupported features");
+        let (_, findings) = team_with_warnings.to_connection_config();
+        assert!(
+            findings.has_warnings(),
+            "Expected warnings for unsupported features"
+        );
 
         // Test that supported features don't generate warnings
         let team_without_warnings = Team {
@@ -1273,8 +1344,11 @@ mod tests {
             ..Default::default()
         };
 
-        let (_, has_warnings) = team_without_warnings.to_connection_config();
-        assert!(!has_warnings, "Expected no warnings for supported features");
+        let (_, findings) = team_without_warnings.to_connection_config();
+        assert!(
+            !findings.has_warnings(),
+            "Expected no warnings for supported features"
+        );
     }
 
     #[test]
@@ -1303,9 +1377,9 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
+        let (config, findings) = team.to_connection_config();
         assert!(
-            !has_warnings,
+            !findings.has_warnings(),
             "Same interval and validation should not warn"
         );
 
@@ -1344,9 +1418,9 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
+        let (config, findings) = team.to_connection_config();
         assert!(
-            has_warnings,
+            findings.has_warnings(),
             "Different intervals and missed_max should warn"
         );
 
@@ -1387,8 +1461,11 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(has_warnings, "Different validation settings should warn");
+        let (config, findings) = team.to_connection_config();
+        assert!(
+            findings.has_warnings(),
+            "Different validation settings should warn"
+        );
 
         if let ConnectionConfig::Bond(bond) = config {
             // Should use first validation setting
@@ -1414,8 +1491,8 @@ mod tests {
             ..Default::default()
         };
 
-        let (_, has_warnings) = team.to_connection_config();
-        assert!(!has_warnings, "Single ethtool should not warn");
+        let (_, findings) = team.to_connection_config();
+        assert!(!findings.has_warnings(), "Single ethtool should not warn");
     }
 
     #[test]
@@ -1433,8 +1510,8 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(!has_warnings, "Single arp_ping should not warn");
+        let (config, findings) = team.to_connection_config();
+        assert!(!findings.has_warnings(), "Single arp_ping should not warn");
 
         if let ConnectionConfig::Bond(bond) = config {
             assert_eq!(bond.options.0.get("arp_interval").unwrap(), "1000");
@@ -1464,8 +1541,11 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(has_warnings, "Both ethtool and arp_ping should warn");
+        let (config, findings) = team.to_connection_config();
+        assert!(
+            findings.has_warnings(),
+            "Both ethtool and arp_ping should warn"
+        );
 
         if let ConnectionConfig::Bond(bond) = config {
             // Should use ethtool (miimon), not arp_ping
@@ -1496,8 +1576,8 @@ mod tests {
             ..Default::default()
         };
 
-        let (_, has_warnings) = team.to_connection_config();
-        assert!(has_warnings, "nsna_ping should warn");
+        let (_, findings) = team.to_connection_config();
+        assert!(findings.has_warnings(), "nsna_ping should warn");
 
         testing_logger::validate(|captured_logs| {
             assert!(captured_logs
@@ -1529,8 +1609,8 @@ mod tests {
             ..Default::default()
         };
 
-        let (_, has_warnings) = team.to_connection_config();
-        assert!(has_warnings, "tipc should warn");
+        let (_, findings) = team.to_connection_config();
+        assert!(findings.has_warnings(), "tipc should warn");
 
         testing_logger::validate(|captured_logs| {
             assert!(captured_logs.iter().any(|log| log.body.contains("tipc")));
@@ -1559,8 +1639,11 @@ mod tests {
             ..Default::default()
         };
 
-        let (config, has_warnings) = team.to_connection_config();
-        assert!(!has_warnings, "Multiple ethtool watches should not warn");
+        let (config, findings) = team.to_connection_config();
+        assert!(
+            !findings.has_warnings(),
+            "Multiple ethtool watches should not warn"
+        );
 
         if let ConnectionConfig::Bond(bond) = config {
             // Should use first ethtool watch
@@ -0,0 +1,459 @@
+use crate::interface::{Interface, LinkPortType, Route};
+use anyhow::Context;
+use cidr::{IpCidr, IpInet};
+use nix::ifaddrs::getifaddrs;
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Network state of an interface as currently configured in the kernel.
+#[derive(Debug, Default, PartialEq)]
+pub struct LiveInterface {
+    pub addresses: Vec<IpInet>,
+    pub routes: Vec<LiveRoute>,
+    pub mtu: Option<u32>,
+    pub master: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct LiveRoute {
+    pub destination: IpCidr,
+    pub gateway: Option<IpAddr>,
+}
+
+/// Differences between the wicked configuration of an interface and the kernel state.
+#[derive(Debug, PartialEq)]
+pub struct InterfaceMismatches {
+    pub interface: String,
+    pub mismatches: Vec<String>,
+}
+
+/// Reads addresses via getifaddrs and MTU, controller and routes from sysfs and procfs.
+pub fn read_live_state() -> Result<HashMap<String, LiveInterface>, anyhow::Error> {
+    let mut live: HashMap<String, LiveInterface> = HashMap::new();
+
+    for entry in fs::read_dir("/sys/class/net").context("Failed to list /sys/class/net")? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let mtu = fs::read_to_string(path.join("mtu"))
+            .ok()
+            .and_then(|mtu| mtu.trim().parse().ok());
+        let master = fs::read_link(path.join("master"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+        live.insert(
+            name,
+            LiveInterface {
+                mtu,
+                master,
+                ..Default::default()
+            },
+        );
+    }
+
+    for ifaddr in getifaddrs()? {
+        let (Some(address), Some(netmask)) = (ifaddr.address, ifaddr.netmask) else {
+            continue;
+        };
+        let inet = if let (Some(address), Some(netmask)) =
+            (address.as_sockaddr_in(), netmask.as_sockaddr_in())
+        {
+            IpInet::new(
+                IpAddr::V4(address.ip()),
+                u32::from(netmask.ip()).count_ones() as u8,
+            )?
+        } else if let (Some(address), Some(netmask)) =
+            (address.as_sockaddr_in6(), netmask.as_sockaddr_in6())
+        {
+            IpInet::new(
+                IpAddr::V6(address.ip()),
+                u128::from(netmask.ip()).count_ones() as u8,
+            )?
+        } else {
+            continue;
+        };
+        live.entry(ifaddr.interface_name)
+            .or_default()
+            .addresses
+            .push(inet);
+    }
+
+    let mut routes = parse_ipv4_routes(&fs::read_to_string("/proc/net/route")?)?;
+    // Not available with ipv6 disabled
+    if let Ok(ipv6_routes) = fs::read_to_string("/proc/net/ipv6_route") {
+        routes.extend(parse_ipv6_routes(&ipv6_routes)?);
+    }
+    for (name, route) in routes {
+        if let Some(interface) = live.get_mut(&name) {
+            interface.routes.push(route);
+        }
+    }
+
+    Ok(live)
+}
+
+/// Parses `/proc/net/route`, addresses are hex encoded in host byte order.
+fn parse_ipv4_routes(content: &str) -> Result<Vec<(String, LiveRoute)>, anyhow::Error> {
+    let parse_addr = |hex: &str| -> Result<Ipv4Addr, anyhow::Error> {
+        Ok(Ipv4Addr::from(u32::from_str_radix(hex, 16)?.to_ne_bytes()))
+    };
+
+    let mut routes = vec![];
+    for line in content.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 8 {
+            continue;
+        }
+        let destination = parse_addr(fields[1])?;
+        let gateway = parse_addr(fields[2])?;
+        let prefix = u32::from(parse_addr(fields[7])?).count_ones() as u8;
+        routes.push((
+            fields[0].to_string(),
+            LiveRoute {
+                destination: IpCidr::new(IpAddr::V4(destination), prefix)?,
+                gateway: (!gateway.is_unspecified()).then_some(IpAddr::V4(gateway)),
+            },
+        ));
+    }
+    Ok(routes)
+}
+
+/// Parses `/proc/net/ipv6_route`, addresses are hex encoded in network byte order.
+fn parse_ipv6_routes(content: &str) -> Result<Vec<(String, LiveRoute)>, anyhow::Error> {
+    let parse_addr = |hex: &str| -> Result<Ipv6Addr, anyhow::Error> {
+        Ok(Ipv6Addr::from(u128::from_str_radix(hex, 16)?))
+    };
+
+    let mut routes = vec![];
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let destination = parse_addr(fields[0])?;
+        let prefix = u8::from_str_radix(fields[1], 16)?;
+        let gateway = parse_addr(fields[4])?;
+        routes.push((
+            fields[9].to_string(),
+            LiveRoute {
+                destination: IpCidr::new(IpAddr::V6(destination), prefix)?,
+                gateway: (!gateway.is_unspecified()).then_some(IpAddr::V6(gateway)),
+            },
+        ));
+    }
+    Ok(routes)
+}
+
+fn expected_addresses(interface: &Interface) -> Vec<String> {
+    let static4 = interface
+        .ipv4_static
+        .as_ref()
+        .and_then(|s| s.addresses.as_ref());
+    let static6 = interface
+        .ipv6_static
+        .as_ref()
+        .and_then(|s| s.addresses.as_ref());
+    static4
+        .into_iter()
+        .chain(static6)
+        .flatten()
+        .map(|a| a.local.clone())
+        .collect()
+}
+
+fn expected_routes(interface: &Interface) -> Vec<&Route> {
+    let static4 = interface
+        .ipv4_static
+        .as_ref()
+        .and_then(|s| s.routes.as_ref());
+    let static6 = interface
+        .ipv6_static
+        .as_ref()
+        .and_then(|s| s.routes.as_ref());
+    static4.into_iter().chain(static6).flatten().collect()
+}
+
+/// Compares the static addresses, routes, MTU and controller of the wicked interfaces with
+/// the kernel state. Addresses and routes that only exist in the kernel (e.g. from DHCP) are
+/// not reported.
+pub fn verify(
+    interfaces: &[Interface],
+    live: &HashMap<String, LiveInterface>,
+) -> Vec<InterfaceMismatches> {
+    let mut result = vec![];
+
+    for interface in interfaces {
+        let mut mismatches = vec![];
+        let Some(live_interface) = live.get(&interface.name) else {
+            result.push(InterfaceMismatches {
+                interface: interface.name.clone(),
+                mismatches: vec!["interface doesn't exist".to_string()],
+            });
+            continue;
+        };
+
+        if let Some(mtu) = interface.link.mtu {
+            if live_interface.mtu != Some(mtu) {
+                mismatches.push(format!(
+                    "mtu is {}, expected {mtu}",
+                    live_interface
+                        .mtu
+                        .map_or("unknown".to_string(), |m| m.to_string())
+                ));
+            }
+        }
+
+        // OVS ports are enslaved to ovs-system in the kernel, not to the bridge
+        let ovs_port = interface
+            .link
+            .port
+            .as_ref()
+            .is_some_and(|p| matches!(p.port_type, LinkPortType::OvsBridge));
+        if let Some(master) = &interface.link.master {
+            if !ovs_port && live_interface.master.as_ref() != Some(master) {
+                mismatches.push(format!(
+                    "controller is {}, expected {master}",
+                    live_interface.master.as_deref().unwrap_or("none")
+                ));
+            }
+        }
+
+        for address in expected_addresses(interface) {
+            match address.parse::<IpInet>() {
+                Ok(inet) if live_interface.addresses.contains(&inet) => {}
+                Ok(_) => mismatches.push(format!("address {address} is missing")),
+                Err(e) => mismatches.push(format!("invalid address {address}: {e}")),
+            }
+        }
+
+        // Routes that can't be migrated are already reported by the migration
+        let routes = expected_routes(interface)
+            .into_iter()
+            .filter_map(|r| r.to_ip_routes(true).ok())
+            .flatten();
+        for (route, attributes) in routes {
+            // Only the main table is listed in /proc/net/route
+            if attributes.contains_key("table") || attributes.contains_key("type") {
+                continue;
+            }
+            let destination = route.destination.network();
+            if !live_interface
+                .routes
+                .iter()
+                .any(|r| r.destination == destination && r.gateway == route.next_hop)
+            {
+                mismatches.push(match route.next_hop {
+                    Some(gateway) => format!("route {destination} via {gateway} is missing"),
+                    None => format!("route {destination} is missing"),
+                });
+            }
+        }
+
+        if !mismatches.is_empty() {
+            result.push(InterfaceMismatches {
+                interface: interface.name.clone(),
+                mismatches,
+            });
+        }
+    }
+
+    result
+}
+
+/// Logs all mismatches, returns an error if there are any.
+pub fn verify_live_state(interfaces: &[Interface]) -> Result<(), anyhow::Error> {
+    let live = read_live_state()?;
+    let result = verify(interfaces, &live);
+
+    for interface in interfaces {
+        match result.iter().find(|m| m.interface == interface.name) {
+            Some(mismatches) => {
+                for mismatch in &mismatches.mismatches {
+                    log::warn!("{}: {mismatch}", interface.name);
+                }
+            }
+            None => log::info!("{}: matches the wicked configuration", interface.name),
+        }
+    }
+
+    if !result.is_empty() {
+        anyhow::bail!(
+            "{} of {} interfaces don't match the wicked configuration",
+            result.len(),
+            interfaces.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::{Address, Ipv4Static, Link, Nexthop};
+    use crate::{Cli, Commands};
+    use clap::error::ErrorKind;
+    use clap::Parser;
+
+    #[test]
+    fn test_verify_command() {
+        let result = Cli::try_parse_from(&["wicked2nm", "verify", "some_file.xml"]).unwrap();
+        let Commands::Verify { paths } = result.command else {
+            panic!("Expected the verify command");
+        };
+        assert_eq!(paths, vec!["some_file.xml".to_string()]);
+
+        let result = Cli::try_parse_from(&["wicked2nm", "verify", "--ifcfg"]).unwrap();
+        assert!(matches!(result.command, Commands::Verify { .. }));
+
+        let result = Cli::try_parse_from(&["wicked2nm", "verify"]);
+        assert_eq!(
+            result.unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn test_parse_routes() {
+        let routes = parse_ipv4_routes(
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+             eth0\t00000000\t0102A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n\
+             eth0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n",
+        )
+        .unwrap();
+        if cfg!(target_endian = "little") {
+            assert_eq!(
+                routes,
+                vec![
+                    (
+                        "eth0".to_string(),
+                        LiveRoute {
+                            destination: "0.0.0.0/0".parse().unwrap(),
+                            gateway: Some("192.168.2.1".parse().unwrap()),
+                        }
+                    ),
+                    (
+                        "eth0".to_string(),
+                        LiveRoute {
+                            destination: "192.168.2.0/24".parse().unwrap(),
+                            gateway: None,
+                        }
+                    ),
+                ]
+            );
+        }
+
+        let routes = parse_ipv6_routes(
+            "20010db8000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0\n\
+             00000000000000000000000000000000 00 00000000000000000000000000000000 00 20010db8000000000000000000000001 00000400 00000001 00000000 00000003     eth0\n",
+        )
+        .unwrap();
+        assert_eq!(
+            routes,
+            vec![
+                (
+                    "eth0".to_string(),
+                    LiveRoute {
+                        destination: "2001:db8::/64".parse().unwrap(),
+                        gateway: None,
+                    }
+                ),
+                (
+                    "eth0".to_string(),
+                    LiveRoute {
+                        destination: "::/0".parse().unwrap(),
+                        gateway: Some("2001:db8::1".parse().unwrap()),
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let interfaces = vec![
+            Interface {
+                name: "eth0".to_string(),
+                link: Link {
+                    mtu: Some(9000),
+                    ..Default::default()
+                },
+                ipv4_static: Some(Ipv4Static {
+                    addresses: Some(vec![Address {
+                        local: "192.168.2.10/24".to_string(),
+                        ..Default::default()
+                    }]),
+                    routes: Some(vec![Route {
+                        nexthops: Some(vec![Nexthop {
+                            gateway: "192.168.2.1".to_string(),
+                            ..Default::default()
+                        }]),
+                        ..Default::default()
+                    }]),
+                }),
+                ..Default::default()
+            },
+            Interface {
+                name: "eth1".to_string(),
+                link: Link {
+                    master: Some("bond0".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Interface {
+                name: "eth2".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let mut live = HashMap::from([
+            (
+                "eth0".to_string(),
+                LiveInterface {
+                    addresses: vec!["192.168.2.10/24".parse().unwrap()],
+                    routes: vec![LiveRoute {
+                        destination: "0.0.0.0/0".parse().unwrap(),
+                        gateway: Some("192.168.2.1".parse().unwrap()),
+                    }],
+                    mtu: Some(9000),
+                    master: None,
+                },
+            ),
+            (
+                "eth1".to_string(),
+                LiveInterface {
+                    master: Some("bond0".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        assert_eq!(
+            verify(&interfaces, &live),
+            vec![InterfaceMismatches {
+                interface: "eth2".to_string(),
+                mismatches: vec!["interface doesn't exist".to_string()],
+            }]
+        );
+
+        let eth0 = live.get_mut("eth0").unwrap();
+        eth0.mtu = Some(1500);
+        eth0.addresses.clear();
+        eth0.routes.clear();
+        live.get_mut("eth1").unwrap().master = None;
+        let result = verify(&interfaces[..2], &live);
+        assert_eq!(
+            result[0].mismatches,
+            vec![
+                "mtu is 1500, expected 9000",
+                "address 192.168.2.10/24 is missing",
+                "route 0.0.0.0/0 via 192.168.2.1 is missing",
+            ]
+        );
+        assert_eq!(
+            result[1].mismatches,
+            vec!["controller is none, expected bond0"]
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use crate::extra::ExtraSettings;
+use crate::interface::Interface;
+use crate::reader::replace_colons;
+use anyhow::Context;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::Path;
+
+/// State wicked persisted in `/var/lib/wicked`, needed to keep the DHCP client identity.
+#[derive(Debug, Default, PartialEq)]
+pub struct WickedState {
+    pub default_duid: Option<String>,
+    pub duids: HashMap<String, String>,
+    pub iaids: HashMap<String, u32>,
+    /// Addresses of the DHCPv4 leases by interface, only read with `--import-leases`
+    pub leases4: HashMap<String, Ipv4Addr>,
+    /// Interfaces with a DHCPv6 lease, only read with `--import-leases`
+    pub leases6: Vec<String>,
+}
+
+/// `duid.xml`, older wicked versions only store the default DUID as text of `<duid>`.
+#[derive(Debug, Deserialize)]
+struct DuidMap {
+    default: Option<String>,
+    #[serde(rename = "device", default)]
+    devices: Vec<DeviceValue>,
+    #[serde(rename = "$text")]
+    duid: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IaidMap {
+    #[serde(rename = "device", default)]
+    devices: Vec<DeviceValue>,
+}
+
+/// `lease-<interface>-dhcp-ipv4.xml`, only the leased address is needed.
+#[derive(Debug, Deserialize)]
+struct Lease {
+    #[serde(rename = "ipv4-dhcp")]
+    ipv4_dhcp: Option<LeaseDhcp4>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LeaseDhcp4 {
+    address: Option<Ipv4Addr>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceValue {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "$text")]
+    value: String,
+}
+
+impl WickedState {
+    pub fn duid(&self, interface: &str) -> Option<&str> {
+        self.duids
+            .get(interface)
+            .or(self.default_duid.as_ref())
+            .map(String::as_str)
+    }
+
+    /// Sets the DUID and IAID wicked used for `interface`, so DHCP servers keep seeing the
+    /// same client.
+    pub fn add_dhcp_identity(&self, interface: &Interface, extra: &mut ExtraSettings) {
+        if interface.ipv4_dhcp.is_none() && interface.ipv6_dhcp.is_none() {
+            return;
+        }
+        // The RFC 4361 client-id of DHCPv4 uses the ipv6 DUID as well
+        if let Some(duid) = self.duid(&interface.name) {
+            extra.set("ipv6", "dhcp-duid", duid);
+        }
+        if let Some(iaid) = self.iaids.get(&interface.name) {
+            if interface.ipv4_dhcp.is_some() {
+                extra.set("ipv4", "dhcp-iaid", iaid);
+            }
+            if interface.ipv6_dhcp.is_some() {
+                extra.set("ipv6", "dhcp-iaid", iaid);
+            }
+        }
+    }
+
+    /// Sets the address of the DHCPv4 lease wicked had on `interface`, to be requested again.
+    pub fn add_lease(&self, interface: &Interface, extra: &mut ExtraSettings) {
+        if interface.ipv4_dhcp.is_none() {
+            return;
+        }
+        if let Some(address) = self.leases4.get(&interface.name) {
+            extra.lease_address4 = Some(*address);
+        }
+    }
+
+    /// Reads the DHCPv4 leases of all interfaces in `dir`, of DHCPv6 leases only the interface
+    /// is recorded.
+    pub fn read_leases(&mut self, dir: &Path) -> Result<(), anyhow::Error> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let Some(name) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("lease-"))
+            else {
+                continue;
+            };
+            if let Some(interface) = name.strip_suffix("-dhcp-ipv6.xml") {
+                self.leases6.push(interface.to_string());
+                continue;
+            }
+            let Some(interface) = name.strip_suffix("-dhcp-ipv4.xml") else {
+                continue;
+            };
+            let contents = fs::read_to_string(&path)?;
+            let lease: Lease = quick_xml::de::from_str(&replace_colons(&contents))
+                .with_context(|| format!("Invalid {}", path.display()))?;
+            if let Some(address) = lease.ipv4_dhcp.and_then(|dhcp| dhcp.address) {
+                self.leases4.insert(interface.to_string(), address);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn read_wicked_state(dir: &Path) -> Result<WickedState, anyhow::Error> {
+    let mut state = WickedState::default();
+
+    let duid_path = dir.join("duid.xml");
+    if duid_path.exists() {
+        let contents = fs::read_to_string(&duid_path)?;
+        let duid_map: DuidMap = quick_xml::de::from_str(&contents)
+            .with_context(|| format!("Invalid {}", duid_path.display()))?;
+        state.default_duid = duid_map
+            .default
+            .or(duid_map.duid)
+            .map(|duid| duid.trim().to_string())
+            .filter(|duid| !duid.is_empty());
+        for device in duid_map.devices {
+            state
+                .duids
+                .insert(device.name, device.value.trim().to_string());
+        }
+    }
+
+    let iaid_path = dir.join("iaid.xml");
+    if iaid_path.exists() {
+        let contents = fs::read_to_string(&iaid_path)?;
+        let iaid_map: IaidMap = quick_xml::de::from_str(&contents)
+            .with_context(|| format!("Invalid {}", iaid_path.display()))?;
+        for device in iaid_map.devices {
+            let iaid =
+                device.value.trim().parse().with_context(|| {
+                    format!("Invalid IAID '{}' of {}", device.value, device.name)
+                })?;
+            state.iaids.insert(device.name, iaid);
+        }
+    }
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interface::{Ipv4Dhcp, Ipv6Dhcp};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_read_wicked_state() {
+        let dir = std::env::temp_dir().join(format!("wicked2nm-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("duid.xml"),
+            r#"<duid>
+  <default>00:03:00:01:52:54:00:12:34:56</default>
+  <device name="eth1">00:04:11:22:33:44</device>
+</duid>"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("iaid.xml"),
+            r#"<iaid>
+  <device name="eth0">1384179584</device>
+</iaid>"#,
+        )
+        .unwrap();
+
+        let state = read_wicked_state(&dir).unwrap();
+        assert_eq!(state.duid("eth0"), Some("00:03:00:01:52:54:00:12:34:56"));
+        assert_eq!(state.duid("eth1"), Some("00:04:11:22:33:44"));
+
+        let interface = Interface {
+            name: "eth0".to_string(),
+            ipv4_dhcp: Some(Ipv4Dhcp::default()),
+            ipv6_dhcp: Some(Ipv6Dhcp::default()),
+            ..Default::default()
+        };
+        let mut extra = ExtraSettings::default();
+        state.add_dhcp_identity(&interface, &mut extra);
+        assert_eq!(
+            extra.properties[&("ipv6".to_string(), "dhcp-duid".to_string())],
+            "00:03:00:01:52:54:00:12:34:56"
+        );
+        assert_eq!(
+            extra.properties[&("ipv4".to_string(), "dhcp-iaid".to_string())],
+            "1384179584"
+        );
+
+        // The old format only has the default DUID
+        fs::write(dir.join("duid.xml"), "<duid>00:01:00:01:aa:bb</duid>").unwrap();
+        fs::remove_file(dir.join("iaid.xml")).unwrap();
+        let state = read_wicked_state(&dir).unwrap();
+        assert_eq!(state.default_duid.as_deref(), Some("00:01:00:01:aa:bb"));
+        assert!(state.iaids.is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_leases() {
+        let dir = std::env::temp_dir().join(format!("wicked2nm-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("lease-eth0.10-dhcp-ipv4.xml"),
+            r#"<lease>
+  <family>ipv4</family>
+  <type>dhcp</type>
+  <ipv4:dhcp>
+    <client-id>ff:00:12:34:56:00:03:00:01:52:54:00:12:34:56</client-id>
+    <server-address>192.168.122.1</server-address>
+    <address>192.168.122.226</address>
+    <lease-time>3600</lease-time>
+  </ipv4:dhcp>
+</lease>"#,
+        )
+        .unwrap();
+        fs::write(dir.join("lease-eth0-dhcp-ipv6.xml"), "<lease/>").unwrap();
+
+        let mut state = WickedState::default();
+        state.read_leases(&dir).unwrap();
+        assert_eq!(
+            state.leases4,
+            HashMap::from([("eth0.10".to_string(), "192.168.122.226".parse().unwrap())])
+        );
+        assert_eq!(state.leases6, vec!["eth0".to_string()]);
+
+        let mut interface = Interface {
+            name: "eth0.10".to_string(),
+            ..Default::default()
+        };
+        let mut extra = ExtraSettings::default();
+        state.add_lease(&interface, &mut extra);
+        assert!(extra.is_empty());
+        interface.ipv4_dhcp = Some(Ipv4Dhcp::default());
+        state.add_lease(&interface, &mut extra);
+        assert_eq!(
+            extra.lease_address4,
+            Some("192.168.122.226".parse().unwrap())
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
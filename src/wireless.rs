@@ -1,3 +1,5 @@
+use crate::diagnostic::Diagnostic;
+use crate::report::Findings;
 use crate::MIGRATION_SETTINGS;
 use agama_network::model::{self, WEPAuthAlg, WEPKeyType, WEPSecurity};
 use agama_network::types::SSID;
@@ -377,6 +379,7 @@ fn common_settings_to_config(
     group_ciphers: &[EapGroupCipher],
     pmf: &Option<Pmf>,
     config: &mut model::WirelessConfig,
+    findings: &mut Findings,
 ) {
     config.wpa_protocol_versions = auth_protos
         .iter()
@@ -388,7 +391,11 @@ fn common_settings_to_config(
         match model::PairwiseAlgorithm::try_from(pairwise_cipher) {
             Ok(algo) => pairwise_algorithms.push(algo),
             Err(e) => {
-                log::info!("{e}");
+                findings.info(
+                    Diagnostic::WirelessCipher,
+                    Some("wireless.network.pairwise_cipher"),
+                    e.to_string(),
+                );
                 pairwise_algorithms = vec![];
                 break;
             }
@@ -401,7 +408,11 @@ fn common_settings_to_config(
         match model::GroupAlgorithm::try_from(group_cipher) {
             Ok(algo) => group_algorithms.push(algo),
             Err(e) => {
-                log::info!("{e}");
+                findings.info(
+                    Diagnostic::WirelessCipher,
+                    Some("wireless.network.group_cipher"),
+                    e.to_string(),
+                );
                 group_algorithms = vec![];
                 break;
             }
@@ -465,11 +476,6 @@ fn guess_wireless_security_protocol(
     } else {
         model::SecurityProtocol::WEP
     };
-    log::warn!(
-        "Unsupported key-management protocol(s) '{}' guessing '{}'",
-        mgmt.join(","),
-        result
-    );
     Ok(result)
 }
 
@@ -512,24 +518,35 @@ fn wireless_security_protocol(network: &Network) -> Result<model::SecurityProtoc
     }
 }
 
-impl TryFrom<&Network> for model::ConnectionConfig {
-    type Error = anyhow::Error;
-
-    fn try_from(network: &Network) -> Result<Self, Self::Error> {
+impl Network {
+    pub fn to_connection_config(
+        &self,
+    ) -> Result<(model::ConnectionConfig, Findings), anyhow::Error> {
         let settings = MIGRATION_SETTINGS.get().unwrap();
+        let mut findings = Findings::default();
         let mut config = model::WirelessConfig {
-            ssid: SSID(network.essid.as_bytes().to_vec()),
-            hidden: network.scan_ssid,
+            ssid: SSID(self.essid.as_bytes().to_vec()),
+            hidden: self.scan_ssid,
             ..Default::default()
         };
 
-        let mut sec = wireless_security_protocol(network);
+        let mut sec = wireless_security_protocol(self);
         if sec.is_err() && settings.continue_migration {
-            sec = guess_wireless_security_protocol(network);
+            sec = guess_wireless_security_protocol(self);
+            if let Ok(guessed) = &sec {
+                findings.warn(
+                    Diagnostic::WirelessKeyManagement,
+                    Some("wireless.self.key_management"),
+                    format!(
+                        "Unsupported key-management protocol(s) '{}' guessing '{guessed}'",
+                        self.key_management.join(",")
+                    ),
+                );
+            }
         }
         config.security = sec?;
 
-        if let Some(wpa_psk) = &network.wpa_psk {
+        if let Some(wpa_psk) = &self.wpa_psk {
             config.password = Some(wpa_psk.passphrase.clone());
 
             common_settings_to_config(
@@ -538,25 +555,30 @@ impl TryFrom<&Network> for model::ConnectionConfig {
                 &wpa_psk.group_cipher,
                 &wpa_psk.pmf,
                 &mut config,
+                &mut findings,
             );
         }
-        if let Some(channel) = network.channel {
+        if let Some(channel) = self.channel {
             config.channel = channel;
             if channel <= 14 {
                 config.band = Some(model::WirelessBand::BG);
             } else {
                 config.band = Some(model::WirelessBand::A);
             }
-            log::warn!(
-                "NetworkManager requires setting a band for wireless when a channel is set. The band has been set to \"{}\". This may in certain regions be incorrect.",
-                config.band.unwrap()
+            findings.warn(
+                Diagnostic::WirelessBand,
+                Some("wireless.self.channel"),
+                format!(
+                    "NetworkManager requires setting a band for wireless when a channel is set. The band has been set to \"{}\". This may in certain regions be incorrect.",
+                    config.band.unwrap()
+                ),
             );
         }
-        if let Some(access_point) = &network.access_point {
+        if let Some(access_point) = &self.access_point {
             config.bssid = Some(MacAddr6::from_str(access_point)?);
         }
 
-        if let Some(wep) = &network.wep {
+        if let Some(wep) = &self.wep {
             // filter out `s:`, `h:`, `:`, and `-` of wep keys
             let keys: Vec<String> = wep
                 .key
@@ -578,18 +600,19 @@ impl TryFrom<&Network> for model::ConnectionConfig {
             config.wep_security = Some(wep_security);
         }
 
-        if let Some(wpa_eap) = &network.wpa_eap {
+        if let Some(wpa_eap) = &self.wpa_eap {
             common_settings_to_config(
                 &wpa_eap.auth_proto,
                 &wpa_eap.pairwise_cipher,
                 &wpa_eap.group_cipher,
                 &wpa_eap.pmf,
                 &mut config,
+                &mut findings,
             );
         }
 
-        config.mode = (&network.mode).into();
-        Ok(model::ConnectionConfig::Wireless(config))
+        config.mode = (&self.mode).into();
+        Ok((model::ConnectionConfig::Wireless(config), findings))
     }
 }
 
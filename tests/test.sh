@@ -216,9 +216,15 @@ for test_dir in ${TEST_DIRS[@]}; do
     unset W2NM_NETCONFIG_BASE_DIR
     unset W2NM_NETCONFIG_PATH
     unset W2NM_NETCONFIG_DHCP_PATH
+    # Nothing may end up in the journal or sysctl.d of the host
+    TEST_TMPDIR=$(mktemp -d)
+    export W2NM_SYSCTL_DIR=$TEST_TMPDIR/sysctl.d
+    export W2NM_JOURNAL=$TEST_TMPDIR/journal.json
+    export W2NM_WICKED_STATE_DIR=./wicked_state
     NM_VERSION_lt=
     NM_VERSION_ge=
     TEST_EXPECT_FAIL=false
+    TEST_ROLLBACK=false
     if [ -f  ./ENV ]; then
        set -a && source ./ENV
        set +a
@@ -303,6 +309,42 @@ for test_dir in ${TEST_DIRS[@]}; do
       done
     fi
 
+    if [ -d "./sysctl.d" ]; then
+      for cmp_file in $(ls -1 ./sysctl.d/); do
+          diff_cmd="diff --unified=0 --color=always ./sysctl.d/$cmp_file $W2NM_SYSCTL_DIR/$cmp_file"
+          log_verbose "RUN: $diff_cmd"
+          if $diff_cmd; then
+              echo -e "${GREEN}Migration for sysctl drop-in ${cmp_file} successful${NC}"
+          else
+              error_msg ${test_dir} "$cmp_file didn't match"
+              FAILED_TESTS+=("${test_dir}::compare_sysctl::${cmp_file}")
+          fi
+      done
+    fi
+
+    if [ "$TEST_ROLLBACK" = true ] && [ "$TEST_EXPECT_FAIL" = false ]; then
+      log_verbose "RUN: $MIGRATE_WICKED_BIN rollback"
+      if ! $MIGRATE_WICKED_BIN rollback; then
+          error_msg ${test_dir} "rollback failed"
+          FAILED_TESTS+=("${test_dir}::rollback")
+      else
+          refresh_connections
+          if [ ${#CONNECTIONS[@]} -gt 0 ]; then
+              error_msg ${test_dir} "rollback left connections: ${CONNECTIONS[*]}"
+              FAILED_TESTS+=("${test_dir}::rollback::connections")
+          elif [ -n "$(ls -A "$W2NM_SYSCTL_DIR" 2>/dev/null)" ]; then
+              error_msg ${test_dir} "rollback left sysctl drop-ins in $W2NM_SYSCTL_DIR"
+              FAILED_TESTS+=("${test_dir}::rollback::sysctl")
+          elif [ -f "$W2NM_JOURNAL" ]; then
+              error_msg ${test_dir} "rollback didn't remove the journal"
+              FAILED_TESTS+=("${test_dir}::rollback::journal")
+          else
+              echo -e "${GREEN}Rollback for $test_dir successful${NC}"
+          fi
+      fi
+    fi
+    rm -rf "$TEST_TMPDIR"
+
     [ "$NO_CLEANUP" -gt 0 ] || nm_cleanup
 done
 